@@ -0,0 +1,105 @@
+//! Compares dispatching with `cismute` against `Any::downcast_ref()` and a
+//! `match` on an enum. The matched type is always the last branch, which is
+//! the worst case for a chain of comparisons.
+//!
+//! Run with `cargo bench --features switch`. Criterion isn't used to keep the
+//! crate free of dependencies, so the numbers are rough.
+
+use std::{any::Any, hint::black_box, time::Instant};
+
+use cismute::{DynBranches, Erased};
+
+const ITERATIONS: u32 = 10_000_000;
+
+fn bench(name: &str, mut f: impl FnMut() -> u64) {
+    // warm up
+    for _ in 0..ITERATIONS / 10 {
+        black_box(f());
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<32} {:>8.2} ns/iter",
+        elapsed.as_secs_f64() * 1e9 / f64::from(ITERATIONS)
+    );
+}
+
+macro_rules! suite {
+    ($module:ident: $($type:ident)+; $last:ident) => {
+        mod $module {
+            use super::*;
+
+            $(
+                #[derive(Clone, Copy)]
+                pub struct $type(pub u64);
+            )+
+
+            // only the last variant is constructed, the rest are for the `match`
+            #[allow(dead_code)]
+            pub enum Value {
+                $($type($type),)+
+            }
+
+            pub fn cismute<T: 'static>(val: T) -> u64 {
+                cismute::switch(val, ($(|x: $type| x.0,)+)).unwrap_or(0)
+            }
+
+            pub fn cismute_dyn<T: 'static>() -> Box<dyn DynBranches<u64, T>> {
+                Box::new(Erased::new(($(|x: $type| x.0,)+)))
+            }
+
+            pub fn any(val: &dyn Any) -> u64 {
+                $(
+                    if let Some(x) = val.downcast_ref::<$type>() {
+                        return x.0;
+                    }
+                )+
+                0
+            }
+
+            pub fn enum_match(val: &Value) -> u64 {
+                match val {
+                    $(Value::$type(x) => x.0,)+
+                }
+            }
+
+            pub fn run() {
+                let name = stringify!($module);
+                let val = $last(42);
+                bench(&format!("{name}/cismute"), || cismute(black_box(val)));
+                let branches = cismute_dyn::<$last>();
+                bench(&format!("{name}/cismute dyn"), || {
+                    cismute::switch(black_box(val), black_box(&*branches)).unwrap_or(0)
+                });
+                bench(&format!("{name}/Any::downcast_ref"), || {
+                    any(black_box(&val as &dyn Any))
+                });
+                let erased = Value::$last(val);
+                bench(&format!("{name}/enum match"), || enum_match(black_box(&erased)));
+            }
+        }
+    };
+}
+
+suite!(branches_1: A0; A0);
+suite!(branches_4: A0 A1 A2 A3; A3);
+suite!(
+    branches_16: A0 A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11 A12 A13 A14 A15;
+    A15
+);
+suite!(
+    branches_32:
+        A0 A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11 A12 A13 A14 A15
+        A16 A17 A18 A19 A20 A21 A22 A23 A24 A25 A26 A27 A28 A29 A30 A31;
+    A31
+);
+
+fn main() {
+    branches_1::run();
+    branches_4::run();
+    branches_16::run();
+    branches_32::run();
+}
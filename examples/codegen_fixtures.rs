@@ -0,0 +1,148 @@
+//! Fixtures for `tests/codegen.rs`. Every `*_cismute` function must compile to
+//! the same code as the matching `*_expected` function.
+
+use cismute::Switch;
+
+fn double<T: 'static>(val: T) -> u32 {
+    match cismute::owned::<T, u32>(val) {
+        Ok(x) => x * 2,
+        Err(_) => 0,
+    }
+}
+
+#[no_mangle]
+pub fn owned_cismute(x: u32) -> u32 {
+    double(x)
+}
+
+#[no_mangle]
+pub fn owned_expected(x: u32) -> u32 {
+    x * 2
+}
+
+#[no_mangle]
+pub fn owned_mismatch_cismute(x: u64) -> u32 {
+    double(x)
+}
+
+#[no_mangle]
+pub fn owned_mismatch_expected(_: u64) -> u32 {
+    0
+}
+
+fn increment<T: 'static>(val: &mut T) {
+    if let Ok(x) = cismute::mutable::<T, u32>(val) {
+        *x += 1;
+    }
+}
+
+#[no_mangle]
+pub fn mutable_cismute(x: &mut u32) {
+    increment(x);
+}
+
+#[no_mangle]
+pub fn mutable_expected(x: &mut u32) {
+    *x += 1;
+}
+
+fn len<T: ?Sized + 'static>(val: &T) -> usize {
+    match cismute::reference::<T, str>(val) {
+        Ok(s) => s.len(),
+        Err(_) => 0,
+    }
+}
+
+#[no_mangle]
+pub fn reference_cismute(s: &str) -> usize {
+    len(s)
+}
+
+#[no_mangle]
+pub fn reference_expected(s: &str) -> usize {
+    s.len()
+}
+
+fn describe<T: 'static>(val: T) -> u64 {
+    cismute::switch!(val; T => {
+        x: u8 => u64::from(x) + 1,
+        x: u16 => u64::from(x) + 2,
+        x: u32 => u64::from(x) + 3,
+        x: u64 => x + 4,
+    })
+    .unwrap_or(0)
+}
+
+#[no_mangle]
+pub fn switch_macro_cismute(x: u32) -> u64 {
+    describe(x)
+}
+
+#[no_mangle]
+pub fn switch_macro_expected(x: u32) -> u64 {
+    u64::from(x) + 3
+}
+
+fn describe_builder<T: 'static>(val: T) -> u64 {
+    Switch::owned(val)
+        .on::<u8>(|x| u64::from(x) + 1)
+        .on::<u16>(|x| u64::from(x) + 2)
+        .on::<u32>(|x| u64::from(x) + 3)
+        .on::<u64>(|x| x + 4)
+        .fallback(|_| 0)
+}
+
+#[no_mangle]
+pub fn builder_cismute(x: u16) -> u64 {
+    describe_builder(x)
+}
+
+#[no_mangle]
+pub fn builder_expected(x: u16) -> u64 {
+    u64::from(x) + 2
+}
+
+fn describe_tuple<T: 'static>(val: T) -> u64 {
+    cismute::switch(
+        val,
+        (
+            |x: u8| u64::from(x) + 1,
+            |x: u16| u64::from(x) + 2,
+            |x: u32| u64::from(x) + 3,
+            |x: u64| x + 4,
+        ),
+    )
+    .unwrap_or(0)
+}
+
+#[no_mangle]
+pub fn switch_cismute(x: u64) -> u64 {
+    describe_tuple(x)
+}
+
+#[no_mangle]
+pub fn switch_expected(x: u64) -> u64 {
+    x + 4
+}
+
+type Page = [u8; 4096];
+
+fn checksum<T: 'static>(val: T) -> u8 {
+    match cismute::owned::<T, Page>(val) {
+        Ok(page) => page[0] ^ page[4095],
+        Err(_) => 0,
+    }
+}
+
+// Only two bytes are read, so the page must not be copied on the way
+#[no_mangle]
+pub fn large_cismute(page: Page) -> u8 {
+    checksum(page)
+}
+
+#[no_mangle]
+pub fn large_expected(page: Page) -> u8 {
+    page[0] ^ page[4095]
+}
+
+fn main() {}
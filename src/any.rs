@@ -0,0 +1,825 @@
+use core::{
+    any::{Any, TypeId},
+    fmt,
+    marker::PhantomData,
+    mem,
+    ptr::NonNull,
+    slice,
+};
+
+// Runtime identity of a type, compared the same way as `same_type()`. The name
+// is unknown for values erased by `core::any`, then only ids are compared
+#[derive(Clone, Copy)]
+pub(crate) struct TypeKey {
+    id: TypeId,
+    #[cfg(any(feature = "strict", feature = "paranoid"))]
+    name: Option<&'static str>,
+}
+
+impl TypeKey {
+    #[inline(always)]
+    pub(crate) fn of<T: ?Sized + 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            #[cfg(any(feature = "strict", feature = "paranoid"))]
+            name: Some(core::any::type_name::<T>()),
+        }
+    }
+
+    #[inline(always)]
+    fn of_any(val: &dyn Any) -> Self {
+        Self {
+            id: val.type_id(),
+            #[cfg(any(feature = "strict", feature = "paranoid"))]
+            name: None,
+        }
+    }
+
+    #[cfg(any(feature = "strict", feature = "paranoid"))]
+    #[inline(always)]
+    fn same_name(self, other: Self) -> bool {
+        match (self.name, other.name) {
+            (Some(name), Some(other)) => name == other,
+            _ => true,
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn is<T: ?Sized + 'static>(self) -> bool {
+        self == Self::of::<T>()
+    }
+}
+
+impl PartialEq for TypeKey {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        let same = self.id == other.id;
+        #[cfg(feature = "paranoid")]
+        if same && !self.same_name(*other) {
+            crate::paranoid_abort("cismute: types with the same `TypeId` have different names");
+        }
+        #[cfg(feature = "strict")]
+        let same = same && self.same_name(*other);
+        same
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Auto traits of erased values, spelled as a trait object: `dyn Any`,
+/// `dyn Any + Send`, `dyn Any + Sync` or `dyn Any + Send + Sync`. Erased types
+/// are [`Send`] and [`Sync`] if a reference to (or a box of) this trait object
+/// would be, and only values implementing the same traits can be erased with
+/// [`AnyRef::bounded()`] and friends. `dyn Any` is the default.
+///
+/// ```rust
+/// use std::{any::Any, thread};
+///
+/// use cismute::AnyRef;
+///
+/// let columns = [42_u32, 1];
+/// let val = AnyRef::<dyn Any + Sync>::bounded(&columns);
+/// thread::scope(|s| {
+///     s.spawn(|| assert_eq!(val.downcast_ref::<[u32; 2]>(), Some(&[42, 1])));
+/// });
+/// ```
+///
+/// Values that don't implement the traits can't be erased this way:
+///
+/// ```rust,compile_fail
+/// use std::{any::Any, cell::Cell};
+///
+/// let cell = Cell::new(42_u32);
+/// let val = cismute::AnyRef::<dyn Any + Sync>::bounded(&cell);
+/// ```
+pub trait AutoTraits<T: ?Sized>: sealed::Sealed {}
+
+impl sealed::Sealed for dyn Any {}
+impl sealed::Sealed for dyn Any + Send {}
+impl sealed::Sealed for dyn Any + Sync {}
+impl sealed::Sealed for dyn Any + Send + Sync {}
+
+impl<T: ?Sized> AutoTraits<T> for dyn Any {}
+impl<T: ?Sized + Send> AutoTraits<T> for dyn Any + Send {}
+impl<T: ?Sized + Sync> AutoTraits<T> for dyn Any + Sync {}
+impl<T: ?Sized + Send + Sync> AutoTraits<T> for dyn Any + Send + Sync {}
+
+/// A type-erased shared reference, like `&dyn Any`, but without a vtable, so
+/// any `'static` value can be erased without being wrapped or implementing a
+/// trait.
+///
+/// ```rust
+/// use cismute::AnyRef;
+///
+/// fn describe(val: AnyRef<'_>) -> String {
+///     if let Some(x) = val.downcast_ref::<i32>() {
+///         format!("got an i32: {x}")
+///     } else {
+///         String::from("got something else")
+///     }
+/// }
+///
+/// assert_eq!(describe(AnyRef::new(&42_i32)), "got an i32: 42");
+/// assert_eq!(describe(AnyRef::new(&'!')), "got something else");
+/// ```
+///
+/// It's neither [`Send`] nor [`Sync`], unless `A` says so, see [`AutoTraits`].
+pub struct AnyRef<'a, A: ?Sized = dyn Any> {
+    ptr: NonNull<()>,
+    key: TypeKey,
+    marker: PhantomData<&'a A>,
+}
+
+impl<'a> AnyRef<'a> {
+    /// Erases the type of the reference.
+    #[inline(always)]
+    pub fn new<T: 'static>(val: &'a T) -> Self {
+        Self::bounded(val)
+    }
+
+    /// Converts from `&dyn Any`. Its vtable isn't kept, so it can't be
+    /// converted back. With the `strict` feature, only [`TypeId`]s are
+    /// compared for such references, since the name of the type is unknown.
+    ///
+    /// ```rust
+    /// use std::any::Any;
+    ///
+    /// let val: &dyn Any = &42_i32;
+    /// let val = cismute::AnyRef::from_any(val);
+    /// assert_eq!(val.downcast_ref::<i32>(), Some(&42));
+    /// ```
+    #[inline(always)]
+    pub fn from_any(val: &'a dyn Any) -> Self {
+        Self {
+            key: TypeKey::of_any(val),
+            ptr: NonNull::from(val).cast(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, A: ?Sized> AnyRef<'a, A> {
+    /// Erases the type of the reference, keeping the auto traits of `A`.
+    #[inline(always)]
+    pub fn bounded<T: 'static>(val: &'a T) -> Self
+    where
+        A: AutoTraits<T>,
+    {
+        Self {
+            ptr: NonNull::from(val).cast(),
+            key: TypeKey::of::<T>(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Forgets the auto traits, e.g. to pass it to code taking `AnyRef<'_>`.
+    #[inline(always)]
+    #[must_use]
+    pub fn unbounded(self) -> AnyRef<'a> {
+        AnyRef {
+            ptr: self.ptr,
+            key: self.key,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the referenced value is of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.key.is::<T>()
+    }
+
+    /// Returns the reference with the original type, if it's `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn downcast_ref<T: 'static>(self) -> Option<&'a T> {
+        // SAFETY: the pointer was created from `&'a T`
+        self.is::<T>()
+            .then(|| unsafe { self.ptr.cast::<T>().as_ref() })
+    }
+}
+
+impl<A: ?Sized> AnyRef<'_, A> {
+    // `ptr` must point to a value of the type of `key`, valid for the lifetime
+    #[inline(always)]
+    pub(crate) unsafe fn from_raw(ptr: NonNull<()>, key: TypeKey) -> Self {
+        Self {
+            ptr,
+            key,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'static> From<&'a T> for AnyRef<'a> {
+    #[inline(always)]
+    fn from(val: &'a T) -> Self {
+        Self::new(val)
+    }
+}
+
+impl<A: ?Sized> Clone for AnyRef<'_, A> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: ?Sized> Copy for AnyRef<'_, A> {}
+
+// SAFETY: it's a shared reference to a value implementing the auto traits of
+// `A`, so it's `Send` and `Sync` the same way `&A` is
+unsafe impl<A: ?Sized + Sync> Send for AnyRef<'_, A> {}
+// SAFETY: see above
+unsafe impl<A: ?Sized + Sync> Sync for AnyRef<'_, A> {}
+
+impl<A: ?Sized> fmt::Debug for AnyRef<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyRef").finish_non_exhaustive()
+    }
+}
+
+/// A type-erased mutable reference, like `&mut dyn Any`, but without a vtable.
+/// See [`AnyRef`].
+///
+/// ```rust
+/// use cismute::AnyMut;
+///
+/// fn reset(mut val: AnyMut<'_>) {
+///     if let Some(x) = val.downcast_mut::<u32>() {
+///         *x = 0;
+///     } else {
+///         let _ = val.take::<String>();
+///     }
+/// }
+///
+/// let (mut x, mut s) = (42_u32, String::from("hi"));
+/// reset(AnyMut::new(&mut x));
+/// reset(AnyMut::new(&mut s));
+/// assert_eq!((x, s.as_str()), (0, ""));
+/// ```
+pub struct AnyMut<'a, A: ?Sized = dyn Any> {
+    ptr: NonNull<()>,
+    key: TypeKey,
+    marker: PhantomData<&'a mut A>,
+}
+
+impl<'a> AnyMut<'a> {
+    /// Erases the type of the reference.
+    #[inline(always)]
+    pub fn new<T: 'static>(val: &'a mut T) -> Self {
+        Self::bounded(val)
+    }
+
+    /// Converts from `&mut dyn Any`, like
+    /// [`AnyRef::from_any()`](AnyRef::from_any()).
+    #[inline(always)]
+    pub fn from_any(val: &'a mut dyn Any) -> Self {
+        Self {
+            key: TypeKey::of_any(val),
+            ptr: NonNull::from(val).cast(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, A: ?Sized> AnyMut<'a, A> {
+    /// Erases the type of the reference, keeping the auto traits of `A`, see
+    /// [`AutoTraits`].
+    #[inline(always)]
+    pub fn bounded<T: 'static>(val: &'a mut T) -> Self
+    where
+        A: AutoTraits<T>,
+    {
+        Self {
+            ptr: NonNull::from(val).cast(),
+            key: TypeKey::of::<T>(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Forgets the auto traits, e.g. to pass it to code taking `AnyMut<'_>`.
+    #[inline(always)]
+    #[must_use]
+    pub fn unbounded(self) -> AnyMut<'a> {
+        AnyMut {
+            ptr: self.ptr,
+            key: self.key,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the referenced value is of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.key.is::<T>()
+    }
+
+    /// Returns a mutable reference with the original type, if it's `T`.
+    #[inline(always)]
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        // SAFETY: the pointer was created from `&'a mut T`, which is borrowed
+        // mutably for the lifetime of the result
+        self.is::<T>()
+            .then(|| unsafe { self.ptr.cast::<T>().as_mut() })
+    }
+
+    /// Returns the reference with the original type, if it's `T`. Returns
+    /// itself back otherwise.
+    #[inline(always)]
+    pub fn into_mut<T: 'static>(self) -> Result<&'a mut T, Self> {
+        if self.is::<T>() {
+            // SAFETY: the pointer was created from `&'a mut T`, and `self` is
+            // consumed
+            Ok(unsafe { self.ptr.cast::<T>().as_mut() })
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Replaces the referenced value with `val` if it's of type `T`, and
+    /// returns the old value. Returns `val` back otherwise.
+    #[inline(always)]
+    pub fn replace<T: 'static>(&mut self, val: T) -> Result<T, T> {
+        match self.downcast_mut::<T>() {
+            Some(slot) => Ok(mem::replace(slot, val)),
+            None => Err(val),
+        }
+    }
+
+    /// Takes the referenced value if it's of type `T`, leaving the default
+    /// value in its place.
+    #[inline(always)]
+    pub fn take<T: Default + 'static>(&mut self) -> Option<T> {
+        self.downcast_mut::<T>().map(mem::take)
+    }
+
+    /// Reborrows for a shorter lifetime, so it can be passed on and used
+    /// afterwards.
+    #[inline(always)]
+    pub fn reborrow(&mut self) -> AnyMut<'_, A> {
+        AnyMut {
+            ptr: self.ptr,
+            key: self.key,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a shared reference to the same value.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_ref(&self) -> AnyRef<'_, A> {
+        AnyRef {
+            ptr: self.ptr,
+            key: self.key,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A: ?Sized> AnyMut<'_, A> {
+    // `ptr` must point to a value of the type of `key`, uniquely borrowed for
+    // the lifetime
+    #[inline(always)]
+    pub(crate) unsafe fn from_raw(ptr: NonNull<()>, key: TypeKey) -> Self {
+        Self {
+            ptr,
+            key,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'static> From<&'a mut T> for AnyMut<'a> {
+    #[inline(always)]
+    fn from(val: &'a mut T) -> Self {
+        Self::new(val)
+    }
+}
+
+impl<'a, A: ?Sized> From<AnyMut<'a, A>> for AnyRef<'a, A> {
+    #[inline(always)]
+    fn from(val: AnyMut<'a, A>) -> Self {
+        Self {
+            ptr: val.ptr,
+            key: val.key,
+            marker: PhantomData,
+        }
+    }
+}
+
+// SAFETY: it's a unique reference to a value implementing the auto traits of
+// `A`, so it's `Send` and `Sync` the same way `&mut A` is
+unsafe impl<A: ?Sized + Send> Send for AnyMut<'_, A> {}
+// SAFETY: see above
+unsafe impl<A: ?Sized + Sync> Sync for AnyMut<'_, A> {}
+
+impl<A: ?Sized> fmt::Debug for AnyMut<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyMut").finish_non_exhaustive()
+    }
+}
+
+/// A type-erased shared slice, carrying the element type, the length and the
+/// size of elements, so it can be passed through non-generic code and
+/// recovered with [`as_slice()`](AnySlice::as_slice()).
+///
+/// ```rust
+/// use cismute::AnySlice;
+///
+/// fn sum(column: AnySlice<'_>) -> Option<u64> {
+///     if let Some(xs) = column.as_slice::<u32>() {
+///         Some(xs.iter().map(|&x| u64::from(x)).sum())
+///     } else {
+///         column.as_slice::<u64>().map(|xs| xs.iter().sum())
+///     }
+/// }
+///
+/// assert_eq!(sum(AnySlice::new(&[1_u32, 2])), Some(3));
+/// assert_eq!(sum(AnySlice::new(&[1_u64, 2])), Some(3));
+/// assert_eq!(sum(AnySlice::new(&['!'])), None);
+/// ```
+pub struct AnySlice<'a, A: ?Sized = dyn Any> {
+    ptr: NonNull<()>,
+    len: usize,
+    stride: usize,
+    key: TypeKey,
+    marker: PhantomData<&'a A>,
+}
+
+impl<'a> AnySlice<'a> {
+    /// Erases the element type of the slice.
+    #[inline(always)]
+    pub fn new<T: 'static>(val: &'a [T]) -> Self {
+        Self::bounded(val)
+    }
+}
+
+impl<'a, A: ?Sized> AnySlice<'a, A> {
+    /// Erases the element type of the slice, keeping the auto traits of `A`,
+    /// see [`AutoTraits`].
+    #[inline(always)]
+    pub fn bounded<T: 'static>(val: &'a [T]) -> Self
+    where
+        A: AutoTraits<T>,
+    {
+        Self {
+            ptr: NonNull::from(val).cast(),
+            len: val.len(),
+            stride: size_of::<T>(),
+            key: TypeKey::of::<T>(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Forgets the auto traits, e.g. to pass it to code taking
+    /// `AnySlice<'_>`.
+    #[inline(always)]
+    #[must_use]
+    pub fn unbounded(self) -> AnySlice<'a> {
+        AnySlice {
+            ptr: self.ptr,
+            len: self.len,
+            stride: self.stride,
+            key: self.key,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the size of an element in bytes.
+    #[inline(always)]
+    #[must_use]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns `true` if elements are of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.key.is::<T>()
+    }
+
+    /// Returns the slice with the original element type, if it's `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_slice<T: 'static>(self) -> Option<&'a [T]> {
+        // SAFETY: the pointer and the length were taken from `&'a [T]`
+        self.is::<T>()
+            .then(|| unsafe { slice::from_raw_parts(self.ptr.cast::<T>().as_ptr(), self.len) })
+    }
+
+    /// Returns an erased reference to the element at `index`, if it's in
+    /// bounds.
+    #[inline(always)]
+    #[must_use]
+    pub fn get(self, index: usize) -> Option<AnyRef<'a, A>> {
+        // SAFETY: the index is in bounds, so the element is inside the slice
+        (index < self.len)
+            .then(|| unsafe { AnyRef::from_raw(self.ptr.byte_add(index * self.stride), self.key) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: ?Sized> AnySlice<'_, A> {
+    // `ptr` must point to `len` values of the type of `key`, `stride` bytes
+    // apart, valid for the lifetime
+    #[inline(always)]
+    pub(crate) unsafe fn from_raw(
+        ptr: NonNull<()>,
+        len: usize,
+        stride: usize,
+        key: TypeKey,
+    ) -> Self {
+        Self {
+            ptr,
+            len,
+            stride,
+            key,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'static> From<&'a [T]> for AnySlice<'a> {
+    #[inline(always)]
+    fn from(val: &'a [T]) -> Self {
+        Self::new(val)
+    }
+}
+
+impl<A: ?Sized> Clone for AnySlice<'_, A> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: ?Sized> Copy for AnySlice<'_, A> {}
+
+// SAFETY: it's a shared slice of values implementing the auto traits of `A`,
+// so it's `Send` and `Sync` the same way `&A` is
+unsafe impl<A: ?Sized + Sync> Send for AnySlice<'_, A> {}
+// SAFETY: see above
+unsafe impl<A: ?Sized + Sync> Sync for AnySlice<'_, A> {}
+
+impl<A: ?Sized> fmt::Debug for AnySlice<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnySlice")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A type-erased mutable slice, see [`AnySlice`].
+///
+/// ```rust
+/// use cismute::AnySliceMut;
+///
+/// fn clear(mut column: AnySliceMut<'_>) {
+///     if let Some(xs) = column.as_mut_slice::<u32>() {
+///         xs.fill(0);
+///     }
+/// }
+///
+/// let mut xs = [1_u32, 2];
+/// clear(AnySliceMut::new(&mut xs));
+/// assert_eq!(xs, [0, 0]);
+/// ```
+pub struct AnySliceMut<'a, A: ?Sized = dyn Any> {
+    ptr: NonNull<()>,
+    len: usize,
+    stride: usize,
+    key: TypeKey,
+    marker: PhantomData<&'a mut A>,
+}
+
+impl<'a> AnySliceMut<'a> {
+    /// Erases the element type of the slice.
+    #[inline(always)]
+    pub fn new<T: 'static>(val: &'a mut [T]) -> Self {
+        Self::bounded(val)
+    }
+}
+
+impl<'a, A: ?Sized> AnySliceMut<'a, A> {
+    /// Erases the element type of the slice, keeping the auto traits of `A`,
+    /// see [`AutoTraits`].
+    #[inline(always)]
+    pub fn bounded<T: 'static>(val: &'a mut [T]) -> Self
+    where
+        A: AutoTraits<T>,
+    {
+        Self {
+            len: val.len(),
+            ptr: NonNull::from(val).cast(),
+            stride: size_of::<T>(),
+            key: TypeKey::of::<T>(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Forgets the auto traits, e.g. to pass it to code taking
+    /// `AnySliceMut<'_>`.
+    #[inline(always)]
+    #[must_use]
+    pub fn unbounded(self) -> AnySliceMut<'a> {
+        AnySliceMut {
+            ptr: self.ptr,
+            len: self.len,
+            stride: self.stride,
+            key: self.key,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the size of an element in bytes.
+    #[inline(always)]
+    #[must_use]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns `true` if elements are of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.key.is::<T>()
+    }
+
+    /// Returns a mutable slice with the original element type, if it's `T`.
+    #[inline(always)]
+    pub fn as_mut_slice<T: 'static>(&mut self) -> Option<&mut [T]> {
+        // SAFETY: the pointer and the length were taken from `&'a mut [T]`,
+        // which is borrowed mutably for the lifetime of the result
+        self.is::<T>()
+            .then(|| unsafe { slice::from_raw_parts_mut(self.ptr.cast::<T>().as_ptr(), self.len) })
+    }
+
+    /// Returns the slice with the original element type, if it's `T`. Returns
+    /// itself back otherwise.
+    #[inline(always)]
+    pub fn into_mut_slice<T: 'static>(self) -> Result<&'a mut [T], Self> {
+        if self.is::<T>() {
+            // SAFETY: the pointer and the length were taken from
+            // `&'a mut [T]`, and `self` is consumed
+            Ok(unsafe { slice::from_raw_parts_mut(self.ptr.cast::<T>().as_ptr(), self.len) })
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Returns an erased mutable reference to the element at `index`, if it's
+    /// in bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<AnyMut<'_, A>> {
+        // SAFETY: the index is in bounds, so the element is inside the slice,
+        // which is borrowed mutably
+        (index < self.len)
+            .then(|| unsafe { AnyMut::from_raw(self.ptr.byte_add(index * self.stride), self.key) })
+    }
+
+    /// Returns a shared slice of the same elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_any_slice(&self) -> AnySlice<'_, A> {
+        AnySlice {
+            ptr: self.ptr,
+            len: self.len,
+            stride: self.stride,
+            key: self.key,
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: ?Sized> AnySliceMut<'_, A> {
+    // `ptr` must point to `len` values of the type of `key`, `stride` bytes
+    // apart, uniquely borrowed for the lifetime
+    #[inline(always)]
+    pub(crate) unsafe fn from_raw(
+        ptr: NonNull<()>,
+        len: usize,
+        stride: usize,
+        key: TypeKey,
+    ) -> Self {
+        Self {
+            ptr,
+            len,
+            stride,
+            key,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'static> From<&'a mut [T]> for AnySliceMut<'a> {
+    #[inline(always)]
+    fn from(val: &'a mut [T]) -> Self {
+        Self::new(val)
+    }
+}
+
+// SAFETY: it's a unique slice of values implementing the auto traits of `A`,
+// so it's `Send` and `Sync` the same way `&mut A` is
+unsafe impl<A: ?Sized + Send> Send for AnySliceMut<'_, A> {}
+// SAFETY: see above
+unsafe impl<A: ?Sized + Sync> Sync for AnySliceMut<'_, A> {}
+
+impl<A: ?Sized> fmt::Debug for AnySliceMut<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnySliceMut")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Downcasts `&dyn Any` to `&U`, returning the reference back if failed, the
+/// same way [`cismute::reference`](crate::reference()) does.
+///
+/// ```rust
+/// use std::any::Any;
+///
+/// fn describe(val: &dyn Any) -> String {
+///     match cismute::downcast_ref::<i32>(val) {
+///         Ok(x) => format!("got an i32: {x}"),
+///         Err(val) => match cismute::downcast_ref::<char>(val) {
+///             Ok(c) => format!("got a char: {c}"),
+///             Err(_) => String::from("got something else"),
+///         },
+///     }
+/// }
+///
+/// assert_eq!(describe(&42_i32), "got an i32: 42");
+/// assert_eq!(describe(&'!'), "got a char: !");
+/// assert_eq!(describe(&"?"), "got something else");
+/// ```
+#[inline(always)]
+pub fn downcast_ref<U: 'static>(val: &dyn Any) -> Result<&U, &dyn Any> {
+    val.downcast_ref().ok_or(val)
+}
+
+/// Downcasts `&mut dyn Any` to `&mut U`, returning the reference back if
+/// failed, the same way [`cismute::mutable`](crate::mutable()) does.
+#[inline(always)]
+pub fn downcast_mut<U: 'static>(val: &mut dyn Any) -> Result<&mut U, &mut dyn Any> {
+    if val.is::<U>() {
+        // SAFETY: the value is `U`. `downcast_mut()` can't be used here, since
+        // returning `val` after it doesn't pass the borrow checker
+        Ok(unsafe { &mut *core::ptr::from_mut(val).cast::<U>() })
+    } else {
+        Err(val)
+    }
+}
+
+/// Downcasts `Box<dyn Any>` to `U`, returning the box back if failed, the same
+/// way [`cismute::owned`](crate::owned()) does. This function requires the
+/// `alloc` feature.
+///
+/// ```rust
+/// use std::any::Any;
+///
+/// let val: Box<dyn Any> = Box::new(42_i32);
+/// let val = cismute::downcast_box::<char>(val).unwrap_err();
+/// assert_eq!(cismute::downcast_box::<i32>(val).unwrap(), 42);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline(always)]
+pub fn downcast_box<U: 'static>(
+    val: alloc::boxed::Box<dyn Any>,
+) -> Result<U, alloc::boxed::Box<dyn Any>> {
+    val.downcast().map(|val| *val)
+}
@@ -1,15 +1,192 @@
+use core::marker::PhantomData;
+
 pub trait Branches<R, T, RefT, Args> {
     fn dispatch(self, val: RefT) -> Result<R, RefT>;
+    // Returns the index of the matching branch, or the number of branches
+    fn probe(&self) -> Result<usize, usize>;
+}
+
+/// Object-safe version of a set of branches for [`switch()`](crate::switch()).
+/// Can be boxed, stored in structs and passed around without naming the tuple
+/// type.
+///
+/// Tuples can be converted to it with [`Erased`]. `&dyn DynBranches` can be
+/// passed to [`switch()`](crate::switch()) itself. Named sets of branches can
+/// be declared with [`branches!()`](crate::branches!).
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// use cismute::{DynBranches, Erased};
+///
+/// struct Formatter<T> {
+///     branches: Box<dyn DynBranches<String, T>>,
+/// }
+///
+/// impl<T: Debug + 'static> Formatter<T> {
+///     fn new() -> Self {
+///         Self {
+///             branches: Box::new(Erased::new((
+///                 |x: i32| format!("got an i32: {x}"),
+///                 |x: char| format!("got a char: {x}"),
+///             ))),
+///         }
+///     }
+///
+///     fn format(&self, val: T) -> String {
+///         cismute::switch(val, &*self.branches)
+///             .unwrap_or_else(|x| format!("got something else: {x:?}"))
+///     }
+/// }
+///
+/// assert_eq!(Formatter::new().format(42_i32), "got an i32: 42");
+/// assert_eq!(Formatter::new().format('!'), "got a char: !");
+/// assert_eq!(
+///     Formatter::new().format([1, 2]),
+///     "got something else: [1, 2]"
+/// );
+/// ```
+pub trait DynBranches<R, T, RefT = T> {
+    /// Same as [`switch()`](crate::switch()) with these branches.
+    fn dispatch(&self, val: RefT) -> Result<R, RefT>;
+
+    /// Same as [`probe()`](crate::probe()) with these branches, but returns
+    /// the number of branches if none of them matches.
+    fn probe(&self) -> Result<usize, usize>;
+}
+
+/// Wraps a tuple of branches to implement [`DynBranches`]. The tuple has to be
+/// [`Clone`], since dispatching takes it by value, which is the case for
+/// functions and closures that capture only [`Clone`] values.
+pub struct Erased<Tuple, Args> {
+    branches: Tuple,
+    args: PhantomData<fn() -> Args>,
+}
+
+impl<Tuple, Args> Erased<Tuple, Args> {
+    /// Wraps a tuple of branches.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn new(branches: Tuple) -> Self {
+        Self {
+            branches,
+            args: PhantomData,
+        }
+    }
+}
+
+impl<R, T, RefT, Args, Tuple> DynBranches<R, T, RefT> for Erased<Tuple, Args>
+where
+    Tuple: Branches<R, T, RefT, Args> + Clone,
+{
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn dispatch(&self, val: RefT) -> Result<R, RefT> {
+        self.branches.clone().dispatch(val)
+    }
+
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn probe(&self) -> Result<usize, usize> {
+        self.branches.probe()
+    }
+}
+
+impl<R, T, RefT> Branches<R, T, RefT, ()> for &dyn DynBranches<R, T, RefT> {
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn dispatch(self, val: RefT) -> Result<R, RefT> {
+        DynBranches::dispatch(self, val)
+    }
+
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn probe(&self) -> Result<usize, usize> {
+        DynBranches::probe(*self)
+    }
+}
+
+// Returns the position of the first matching branch, or the number of
+// branches. The types are compared with `cismute::same()`, like in
+// `dispatch()`. It isn't generic, so it's compiled once instead of once per set
+// of branches, and it's public for `branches!()`
+#[doc(hidden)]
+pub fn __position(matches: &[bool]) -> Result<usize, usize> {
+    matches
+        .iter()
+        .position(|&matched| matched)
+        .ok_or(matches.len())
 }
 
 impl<R, T, RefT> Branches<R, T, RefT, ()> for ()
 where
     T: 'static,
 {
-    #[inline(always)]
+    #[cfg_attr(not(feature = "compact"), inline(always))]
     fn dispatch(self, val: RefT) -> Result<R, RefT> {
         Err(val)
     }
+
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn probe(&self) -> Result<usize, usize> {
+        Err(0)
+    }
+}
+
+pub struct Chain<First, Second>(pub First, pub Second);
+
+impl<R, T, RefT, ArgsFirst, ArgsSecond, First, Second> Branches<R, T, RefT, (ArgsFirst, ArgsSecond)>
+    for Chain<First, Second>
+where
+    First: Branches<R, T, RefT, ArgsFirst>,
+    Second: Branches<R, T, RefT, ArgsSecond>,
+{
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn dispatch(self, val: RefT) -> Result<R, RefT> {
+        self.0.dispatch(val).or_else(|val| self.1.dispatch(val))
+    }
+
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn probe(&self) -> Result<usize, usize> {
+        self.0.probe().or_else(|first| {
+            self.1
+                .probe()
+                .map(|index| first + index)
+                .map_err(|second| first + second)
+        })
+    }
+}
+
+pub struct MapOutput<Inner, F>(pub Inner, pub F);
+
+// `R` is a part of `Args` only so it's constrained
+impl<R, NewR, T, RefT, Args, Inner, F> Branches<NewR, T, RefT, (R, Args)> for MapOutput<Inner, F>
+where
+    Inner: Branches<R, T, RefT, Args>,
+    F: FnOnce(R) -> NewR,
+{
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn dispatch(self, val: RefT) -> Result<NewR, RefT> {
+        self.0.dispatch(val).map(self.1)
+    }
+
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn probe(&self) -> Result<usize, usize> {
+        self.0.probe()
+    }
+}
+
+pub struct OrElse<Inner, F>(pub Inner, pub F);
+
+impl<R, T, RefT, Args, Inner, F> Branches<R, T, RefT, Args> for OrElse<Inner, F>
+where
+    Inner: Branches<R, T, RefT, Args>,
+    F: FnOnce(RefT) -> R,
+{
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn dispatch(self, val: RefT) -> Result<R, RefT> {
+        Ok(self.0.dispatch(val).unwrap_or_else(self.1))
+    }
+
+    // fallback is the last branch, which always matches
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn probe(&self) -> Result<usize, usize> {
+        Ok(self.0.probe().unwrap_or_else(|count| count))
+    }
 }
 
 macro_rules! impl_branches {
@@ -31,7 +208,7 @@ macro_rules! impl_branches {
             $fs: FnOnce($refUs) -> R,
         )*
         {
-            #[inline(always)]
+            #[cfg_attr(not(feature = "compact"), inline(always))]
             #[allow(non_snake_case)]
             fn dispatch(self, val: RefT) -> Result<R, RefT> {
                 let ($f, $($fs,)*) = self;
@@ -47,6 +224,12 @@ macro_rules! impl_branches {
                 )*
                 Err(val)
             }
+
+            #[cfg_attr(not(feature = "compact"), inline(always))]
+            #[allow(non_snake_case)]
+            fn probe(&self) -> Result<usize, usize> {
+                __position(&[crate::same::<T, $u>(), $(crate::same::<T, $us>(),)*])
+            }
         }
     };
 }
@@ -65,3 +248,65 @@ impl_branches!(
     F16 F17 F18 F19 F20 F21 F22 F23
     F24 F25 F26 F27 F28 F29 F30 F31;
 );
+
+/// Declares a struct with one handler function per type and implements
+/// [`DynBranches`] for it, so a set of branches can be a named type with
+/// documented fields instead of an anonymous tuple. Fields are function
+/// pointers, so non-capturing closures can be used too. Only owned values are
+/// matched.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// cismute::branches! {
+///     /// Formats integers and characters.
+///     #[derive(Clone, Copy)]
+///     pub struct Formatter -> String {
+///         /// Called for `i32`s.
+///         pub int: i32,
+///         /// Called for `char`s.
+///         pub chr: char,
+///     }
+/// }
+///
+/// fn format<T: Debug + 'static>(formatter: &Formatter, val: T) -> String {
+///     cismute::switch(val, formatter as &dyn cismute::DynBranches<_, _>)
+///         .unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// let formatter = Formatter {
+///     int: |x| format!("got an i32: {x}"),
+///     chr: |x| format!("got a char: {x}"),
+/// };
+/// assert_eq!(format(&formatter, 42_i32), "got an i32: 42");
+/// assert_eq!(format(&formatter, '!'), "got a char: !");
+/// assert_eq!(format(&formatter, [1, 2]), "got something else: [1, 2]");
+/// ```
+#[macro_export]
+macro_rules! branches {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident -> $output:ty {
+            $($(#[$field_attr:meta])* $field_vis:vis $field:ident: $type:ty),+ $(,)?
+        }
+    )+) => {$(
+        $(#[$attr])*
+        $vis struct $name {
+            $($(#[$field_attr])* $field_vis $field: fn($type) -> $output,)+
+        }
+
+        impl<T: 'static> $crate::DynBranches<$output, T> for $name {
+            // `cfg_attr` would check the features of the calling crate here
+            #[inline]
+            fn dispatch(&self, val: T) -> ::core::result::Result<$output, T> {
+                $crate::switch!(val; T => {
+                    $(val: $type => (self.$field)(val),)+
+                })
+            }
+
+            #[inline]
+            fn probe(&self) -> ::core::result::Result<usize, usize> {
+                $crate::__position(&[$($crate::same::<T, $type>(),)+])
+            }
+        }
+    )+};
+}
@@ -0,0 +1,171 @@
+use core::marker::PhantomData;
+
+use crate::{seal::Retype, Cismutable, TypeSet};
+
+/// Builder-style alternative to [`switch!()`](crate::switch!) that matches
+/// one value with multiple types, one type per call. Unlike the tuple-based
+/// [`switch()`](crate::switch()), argument types of the handlers are
+/// inferred, and a mismatched handler is reported right at its `.on()` call.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// use cismute::Switch;
+///
+/// fn specialized_function<T: Debug + 'static>(val: T) -> String {
+///     Switch::owned(val)
+///         .on::<i32>(|x| format!("got an i32: {x}"))
+///         .on::<char>(|x| format!("got a char: {x}"))
+///         .fallback(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// assert_eq!(specialized_function(42_i32), "got an i32: 42");
+/// assert_eq!(specialized_function('!'), "got a char: !");
+/// assert_eq!(specialized_function([1, 2]), "got something else: [1, 2]");
+/// ```
+///
+/// References are matched with [`Switch::reference()`] and
+/// [`Switch::mutable()`], handlers then receive `&U` and `&mut U`
+/// respectively:
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// use cismute::Switch;
+///
+/// fn specialized_function<T: Debug + 'static>(val: &mut T) -> Option<String> {
+///     Switch::mutable(val)
+///         .on::<i32>(|x| {
+///             *x += 1;
+///             format!("got an i32: {x}")
+///         })
+///         .on::<char>(|x| format!("got a char: {x}"))
+///         .finish()
+///         .ok()
+/// }
+///
+/// assert_eq!(specialized_function(&mut 42_i32).unwrap(), "got an i32: 43");
+/// assert_eq!(specialized_function(&mut '!').unwrap(), "got a char: !");
+/// assert_eq!(specialized_function(&mut [1, 2]), None);
+/// ```
+#[must_use]
+pub struct Switch<'a, T: ?Sized, RefT, R> {
+    state: Result<R, RefT>,
+    source: PhantomData<fn() -> &'a T>,
+}
+
+// owned value is its own "reference"
+#[allow(clippy::mismatching_type_param_order)]
+impl<T, R> Switch<'static, T, T, R>
+where
+    T: 'static,
+{
+    /// Starts matching an owned value.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn owned(val: T) -> Self {
+        Self::start(val)
+    }
+}
+
+impl<'a, T, R> Switch<'a, T, &'a T, R>
+where
+    T: ?Sized + 'static,
+{
+    /// Starts matching a reference.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn reference(val: &'a T) -> Self {
+        Self::start(val)
+    }
+}
+
+impl<'a, T, R> Switch<'a, T, &'a mut T, R>
+where
+    T: ?Sized + 'static,
+{
+    /// Starts matching a mutable reference.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn mutable(val: &'a mut T) -> Self {
+        Self::start(val)
+    }
+}
+
+impl<'a, T, RefT, R> Switch<'a, T, RefT, R>
+where
+    T: ?Sized + 'static,
+{
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn start(val: RefT) -> Self {
+        Self {
+            state: Err(val),
+            source: PhantomData,
+        }
+    }
+
+    /// Calls `f` with the cismuted value if it's not matched yet and `T` is
+    /// the same type as `U`.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn on<U>(self, f: impl FnOnce(<RefT as Retype<'a, T, U>>::Output) -> R) -> Self
+    where
+        U: ?Sized + 'static,
+        RefT: Retype<'a, T, U> + Cismutable<'a, T, U, <RefT as Retype<'a, T, U>>::Output>,
+    {
+        let state = match self.state {
+            Err(val) => crate::value::<'a, T, U, RefT, _>(val).map(f),
+            matched => matched,
+        };
+        Self {
+            state,
+            source: PhantomData,
+        }
+    }
+
+    /// Calls `f` with the original value if none of the types matched.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn fallback<F>(self, f: F) -> R
+    where
+        F: FnOnce(RefT) -> R,
+    {
+        self.state.unwrap_or_else(f)
+    }
+
+    /// Returns the result of the matched handler or the original value if none
+    /// of the types matched.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn finish(self) -> Result<R, RefT> {
+        self.state
+    }
+
+    /// Same as [`finish()`](Switch::finish()), but for switches meant to have
+    /// an arm for every type of `S`. This is a runtime check, not an
+    /// exhaustiveness check: a missing arm is only reported when a value of
+    /// that type reaches the switch, as a panic instead of silently falling
+    /// through. Switches missing an arm still compile.
+    ///
+    /// ```rust
+    /// use cismute::{Switch, TypeSet};
+    ///
+    /// type Numbers = TypeSet![i32, u32];
+    ///
+    /// fn describe<T: 'static>(val: T) -> Result<String, T> {
+    ///     Switch::owned(val)
+    ///         .on::<i32>(|x| format!("got an i32: {x}"))
+    ///         .on::<u32>(|x| format!("got a u32: {x}"))
+    ///         .finish_checked::<Numbers>()
+    /// }
+    ///
+    /// assert_eq!(describe(42_u32).unwrap(), "got a u32: 42");
+    /// assert_eq!(describe('!'), Err('!'));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if none of the types matched, but `T` is in `S`, so an arm for
+    /// it is missing.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    #[track_caller]
+    pub fn finish_checked<S: TypeSet>(self) -> Result<R, RefT> {
+        assert!(
+            self.state.is_ok() || !S::contains::<T>(),
+            "cismute: `{}` is in the type set, but the switch has no arm for it",
+            core::any::type_name::<T>(),
+        );
+        self.state
+    }
+}
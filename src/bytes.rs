@@ -0,0 +1,99 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{mutable, reference};
+
+/// Returns the slice as bytes if `T` is `u8`, the most common fast path in IO
+/// code. Same as [`cismute::reference::<[T], [u8]>`](reference()).
+///
+/// ```rust
+/// use std::io::Write;
+///
+/// fn write_all<T: Copy + Into<u32> + 'static>(out: &mut Vec<u8>, items: &[T]) {
+///     if let Some(bytes) = cismute::as_bytes_if_u8(items) {
+///         out.write_all(bytes).unwrap();
+///         return;
+///     }
+///     for &x in items {
+///         out.write_all(&x.into().to_le_bytes()).unwrap();
+///     }
+/// }
+///
+/// let mut out = Vec::new();
+/// write_all(&mut out, &[1_u8, 2]);
+/// write_all(&mut out, &[3_u16]);
+/// assert_eq!(out, [1, 2, 3, 0, 0, 0]);
+/// ```
+#[inline(always)]
+pub fn as_bytes_if_u8<T: 'static>(val: &[T]) -> Option<&[u8]> {
+    reference(val).ok()
+}
+
+/// Returns the slice as mutable bytes if `T` is `u8`, e.g. to read into a
+/// generic buffer. Same as [`cismute::mutable::<[T], [u8]>`](mutable()).
+///
+/// ```rust
+/// use std::io::Read;
+///
+/// fn fill<T: 'static>(buf: &mut [T], mut src: &[u8]) -> usize {
+///     cismute::as_bytes_mut_if_u8(buf).map_or(0, |bytes| src.read(bytes).unwrap())
+/// }
+///
+/// let mut buf = [0_u8; 2];
+/// assert_eq!(fill(&mut buf, &[1, 2, 3]), 2);
+/// assert_eq!(buf, [1, 2]);
+/// assert_eq!(fill(&mut [0_u16; 2], &[1, 2, 3]), 0);
+/// ```
+#[inline(always)]
+pub fn as_bytes_mut_if_u8<T: 'static>(val: &mut [T]) -> Option<&mut [u8]> {
+    mutable(val).ok()
+}
+
+/// Returns the vector as bytes if `T` is `u8`, without copying. Returns it
+/// back otherwise. This function requires the `alloc` feature.
+///
+/// ```rust
+/// fn into_string<T: 'static>(items: Vec<T>) -> Option<String> {
+///     String::from_utf8(cismute::into_bytes_if_u8(items).ok()?).ok()
+/// }
+///
+/// assert_eq!(into_string(b"hi".to_vec()).unwrap(), "hi");
+/// assert_eq!(into_string(vec!['h', 'i']), None);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline(always)]
+pub fn into_bytes_if_u8<T: 'static>(val: Vec<T>) -> Result<Vec<u8>, Vec<T>> {
+    crate::owned(val)
+}
+
+/// Appends `items` to `vec`, copying the slice at once if `T` and `U` are the
+/// same type, and converting items one by one with `convert` otherwise, e.g.
+/// when serializing generic items into a byte buffer. This function requires
+/// the `alloc` feature.
+///
+/// ```rust
+/// fn encode<T: Copy + Into<u32> + 'static>(out: &mut Vec<u8>, items: &[T]) {
+///     // saturating, for brevity
+///     cismute::extend_specialized(out, items, |&x| x.into().min(255) as u8);
+/// }
+///
+/// let mut out = Vec::new();
+/// encode(&mut out, &[1_u8, 2]);
+/// encode(&mut out, &[3_u16, 300]);
+/// assert_eq!(out, [1, 2, 3, 255]);
+/// ```
+///
+/// There's no `std::io::Write` flavor, since the crate is `no_std`; use
+/// [`cismute::as_bytes_if_u8`](as_bytes_if_u8()) with `write_all()` instead.
+#[cfg(feature = "alloc")]
+#[inline(always)]
+pub fn extend_specialized<T: 'static, U: Clone + 'static>(
+    vec: &mut Vec<U>,
+    items: &[T],
+    convert: impl FnMut(&T) -> U,
+) {
+    match reference::<[T], [U]>(items) {
+        Ok(items) => vec.extend_from_slice(items),
+        Err(items) => vec.extend(items.iter().map(convert)),
+    }
+}
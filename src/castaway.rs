@@ -0,0 +1,53 @@
+//! A migration layer for code using [`castaway`](https://docs.rs/castaway).
+//! [`cast!`] is spelled the same way, and [`LifetimeFree`] plays the role of
+//! `castaway::LifetimeFree`, so switching is mostly a matter of changing the
+//! imports. This module requires the `castaway` feature.
+//!
+//! ```rust
+//! use cismute::castaway::cast;
+//!
+//! fn describe<T>(val: T) -> String {
+//!     match cast!(val, u32) {
+//!         Ok(x) => format!("got a u32: {x}"),
+//!         Err(_) => String::from("got something else"),
+//!     }
+//! }
+//!
+//! fn first_byte<T: ?Sized>(val: &T) -> Option<u8> {
+//!     cast!(val, &[u8]).ok()?.first().copied()
+//! }
+//!
+//! assert_eq!(describe(42_u32), "got a u32: 42");
+//! assert_eq!(describe(42_i32), "got something else");
+//! assert_eq!(first_byte(&[42_u8][..]), Some(42));
+//! assert_eq!(first_byte("*"), None);
+//! ```
+//!
+//! Unlike in `castaway`, the target type always has to be [`LifetimeFree`]
+//! (or a reference to one), even if the source type is `'static`; use
+//! [`cismute::owned`](crate::owned()) and friends for other types. With the
+//! `alloc` feature, `String`, `Vec`s and `Box`es are [`LifetimeFree`] too.
+//! Types which implemented `castaway::LifetimeFree` need to implement cismute's
+//! trait instead, with the same safety requirements.
+
+/// Cismutes a value to the given type, like `castaway::cast!`, returning the
+/// value back if it's of a different type. Reference types cismute
+/// references, so `cast!(val, &T)` and `cast!(val, &mut T)` work for unsized
+/// `T` too. See [the module docs](self).
+#[doc(inline)]
+pub use crate::__castaway_cast as cast;
+pub use crate::LifetimeFree;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __castaway_cast {
+    ($val:expr, & $($lifetime:lifetime)?mut $type:ty $(,)?) => {
+        $crate::mutable_lifetime_free::<_, $type>($val)
+    };
+    ($val:expr, & $($lifetime:lifetime)? $type:ty $(,)?) => {
+        $crate::reference_lifetime_free::<_, $type>($val)
+    };
+    ($val:expr, $type:ty $(,)?) => {
+        $crate::owned_lifetime_free::<_, $type>($val)
+    };
+}
@@ -0,0 +1,90 @@
+use core::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use crate::reference;
+
+/// Compares `a` and `b` with `U`'s [`PartialEq`] impl if `T` and `U` are the
+/// same type. Returns `None` if they aren't comparable.
+///
+/// ```rust
+/// fn contains<T: 'static, U: PartialEq + 'static>(items: &[T], needle: &U) -> bool {
+///     items
+///         .iter()
+///         .any(|item| cismute::eq_if_same(item, needle) == Some(true))
+/// }
+///
+/// assert!(contains(&[1, 2], &2));
+/// assert!(!contains(&[1, 2], &3));
+/// assert!(!contains(&[1, 2], &"2"));
+/// ```
+#[inline(always)]
+pub fn eq_if_same<T, U>(a: &T, b: &U) -> Option<bool>
+where
+    T: ?Sized + 'static,
+    U: ?Sized + PartialEq + 'static,
+{
+    reference::<T, U>(a).ok().map(|a| a == b)
+}
+
+/// Compares `a` and `b` with `U`'s [`Ord`] impl if `T` and `U` are the same
+/// type. Returns `None` otherwise.
+///
+/// ```rust
+/// use std::cmp::Ordering;
+///
+/// fn sort<T: 'static>(items: &mut [T], fallback: impl Fn(&T, &T) -> Ordering) {
+///     items
+///         .sort_by(|a, b| cismute::cmp_if_same::<u64, _>(a, b).unwrap_or_else(|| fallback(a, b)));
+/// }
+///
+/// let mut ids = [3_u64, 1, 2];
+/// sort(&mut ids, |_, _| unreachable!());
+/// assert_eq!(ids, [1, 2, 3]);
+///
+/// let mut names = ["b", "a"];
+/// sort(&mut names, |a, b| a.cmp(b));
+/// assert_eq!(names, ["a", "b"]);
+/// ```
+#[inline(always)]
+pub fn cmp_if_same<U, T>(a: &T, b: &T) -> Option<Ordering>
+where
+    U: ?Sized + Ord + 'static,
+    T: ?Sized + 'static,
+{
+    match (reference::<T, U>(a), reference::<T, U>(b)) {
+        (Ok(a), Ok(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Hashes `val` into `state` with `U`'s [`Hash`] impl if `T` and `U` are the
+/// same type. Returns whether it was hashed.
+///
+/// ```rust
+/// use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+///
+/// fn hash_key<T: 'static>(key: &T) -> Option<u64> {
+///     let mut hasher = DefaultHasher::new();
+///     cismute::hash_if_same::<u64, _, _>(key, &mut hasher).then(|| hasher.finish())
+/// }
+///
+/// assert_eq!(hash_key(&1_u64), hash_key(&1_u64));
+/// assert_eq!(hash_key(&1_u32), None);
+/// ```
+#[inline(always)]
+pub fn hash_if_same<U, T, H>(val: &T, state: &mut H) -> bool
+where
+    U: ?Sized + Hash + 'static,
+    T: ?Sized + 'static,
+    H: Hasher,
+{
+    match reference::<T, U>(val) {
+        Ok(val) => {
+            val.hash(state);
+            true
+        }
+        Err(_) => false,
+    }
+}
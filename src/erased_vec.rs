@@ -0,0 +1,267 @@
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, realloc};
+use core::{
+    alloc::Layout,
+    any::Any,
+    fmt,
+    marker::PhantomData,
+    ptr::{self, NonNull},
+};
+
+use crate::{any::TypeKey, AnySlice, AnySliceMut, AutoTraits};
+
+unsafe fn drop_elements<T>(ptr: *mut u8, len: usize) {
+    ptr::slice_from_raw_parts_mut(ptr.cast::<T>(), len).drop_in_place();
+}
+
+#[cold]
+#[track_caller]
+#[allow(clippy::panic)]
+fn capacity_overflow() -> ! {
+    panic!("cismute: capacity overflow")
+}
+
+/// A growable array of elements of a type chosen at runtime, like a column of
+/// an ECS. It only knows the [`TypeId`](core::any::TypeId), the layout and the
+/// drop function of the elements, so code that moves columns around doesn't
+/// need to be generic, and typed access is checked by cismute. This type
+/// requires the `alloc` feature.
+///
+/// ```rust
+/// use cismute::ErasedVec;
+///
+/// let mut columns = vec![ErasedVec::new::<u32>(), ErasedVec::new::<String>()];
+/// for column in &mut columns {
+///     column.push(42_u32).ok();
+///     column.push(String::from("hi")).ok();
+/// }
+///
+/// assert_eq!(columns[0].as_slice::<u32>(), Some(&[42][..]));
+/// assert_eq!(
+///     columns[1].as_slice::<String>(),
+///     Some(&[String::from("hi")][..])
+/// );
+/// assert_eq!(columns[1].as_slice::<u32>(), None);
+/// ```
+///
+/// It's neither [`Send`] nor [`Sync`], unless `A` says so, see [`AutoTraits`].
+pub struct ErasedVec<A: ?Sized = dyn Any> {
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    layout: Layout,
+    key: TypeKey,
+    drop: Option<unsafe fn(*mut u8, usize)>,
+    // the elements implement the auto traits of `A`
+    marker: PhantomData<A>,
+}
+
+impl ErasedVec {
+    /// Creates an empty vector of `T`s. It doesn't allocate until elements are
+    /// pushed.
+    #[inline(always)]
+    #[must_use]
+    pub fn new<T: 'static>() -> Self {
+        Self::bounded::<T>()
+    }
+}
+
+impl<A: ?Sized> ErasedVec<A> {
+    /// Creates an empty vector of `T`s, keeping the auto traits of `A`.
+    #[inline(always)]
+    #[must_use]
+    pub fn bounded<T: 'static>() -> Self
+    where
+        A: AutoTraits<T>,
+    {
+        Self {
+            ptr: NonNull::<T>::dangling().cast(),
+            len: 0,
+            // zero-sized elements never need an allocation
+            cap: if size_of::<T>() == 0 { usize::MAX } else { 0 },
+            layout: Layout::new::<T>(),
+            key: TypeKey::of::<T>(),
+            drop: core::mem::needs_drop::<T>().then_some(drop_elements::<T> as _),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the layout of an element.
+    #[inline(always)]
+    #[must_use]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Returns `true` if elements are of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.key.is::<T>()
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements that fit without reallocating.
+    #[inline(always)]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Reserves space for at least `additional` more elements.
+    ///
+    /// # Panics
+    /// Panics if the new capacity overflows `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self
+            .len
+            .checked_add(additional)
+            .unwrap_or_else(|| capacity_overflow());
+        if required <= self.cap {
+            return;
+        }
+        let cap = required.max(self.cap.saturating_mul(2)).max(4);
+        let layout = self.array_layout(cap);
+        // SAFETY: elements aren't zero-sized, since the capacity of such
+        // vectors is never exceeded, so the layout isn't zero-sized either. The
+        // old allocation, if any, was made with the old capacity
+        let ptr = unsafe {
+            if self.cap == 0 {
+                alloc(layout)
+            } else {
+                realloc(
+                    self.ptr.as_ptr(),
+                    self.array_layout(self.cap),
+                    layout.size(),
+                )
+            }
+        };
+        self.ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+        self.cap = cap;
+    }
+
+    /// Appends an element if it's of type `T`. Returns it back otherwise.
+    ///
+    /// # Panics
+    /// Panics if the new capacity overflows `isize::MAX` bytes.
+    #[inline(always)]
+    pub fn push<T: 'static>(&mut self, val: T) -> Result<(), T> {
+        if !self.is::<T>() {
+            return Err(val);
+        }
+        self.reserve(1);
+        // SAFETY: elements are `T`, and there's space for one more
+        unsafe { self.ptr.cast::<T>().add(self.len).write(val) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, if elements are of type `T`
+    /// and there are any.
+    #[inline(always)]
+    pub fn pop<T: 'static>(&mut self) -> Option<T> {
+        if !self.is::<T>() || self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: elements are `T`, and the last one is no longer counted, so
+        // it's moved out exactly once
+        Some(unsafe { self.ptr.cast::<T>().add(self.len).read() })
+    }
+
+    /// Returns the elements, if they are of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_slice<T: 'static>(&self) -> Option<&[T]> {
+        self.as_any_slice().as_slice()
+    }
+
+    /// Returns the elements mutably, if they are of type `T`.
+    #[inline(always)]
+    pub fn as_mut_slice<T: 'static>(&mut self) -> Option<&mut [T]> {
+        self.as_any_slice_mut().into_mut_slice().ok()
+    }
+
+    /// Returns the elements as an erased slice.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_any_slice(&self) -> AnySlice<'_, A> {
+        // SAFETY: there are `len` initialized elements of the type of `key`
+        unsafe { AnySlice::from_raw(self.ptr.cast(), self.len, self.layout.size(), self.key) }
+    }
+
+    /// Returns the elements as an erased mutable slice.
+    #[inline(always)]
+    pub fn as_any_slice_mut(&mut self) -> AnySliceMut<'_, A> {
+        // SAFETY: there are `len` initialized elements of the type of `key`,
+        // and `self` is borrowed mutably
+        unsafe { AnySliceMut::from_raw(self.ptr.cast(), self.len, self.layout.size(), self.key) }
+    }
+
+    /// Drops the elements after the first `len`. Does nothing if there are
+    /// fewer elements.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = self.len - len;
+        // updated first, so a panicking destructor can't cause double drops
+        self.len = len;
+        if let Some(drop) = self.drop {
+            // SAFETY: the elements after `len` are initialized and no longer
+            // counted, so they are dropped exactly once
+            unsafe { drop(self.ptr.as_ptr().add(len * self.layout.size()), tail) };
+        }
+    }
+
+    /// Drops all elements, keeping the capacity.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    fn array_layout(&self, cap: usize) -> Layout {
+        // the size of a layout is always a multiple of its alignment
+        self.layout
+            .size()
+            .checked_mul(cap)
+            .and_then(|size| Layout::from_size_align(size, self.layout.align()).ok())
+            .unwrap_or_else(|| capacity_overflow())
+    }
+}
+
+impl<A: ?Sized> Drop for ErasedVec<A> {
+    fn drop(&mut self) {
+        self.clear();
+        if self.cap != 0 && self.layout.size() != 0 {
+            // SAFETY: the allocation was made with this capacity
+            unsafe { dealloc(self.ptr.as_ptr(), self.array_layout(self.cap)) };
+        }
+    }
+}
+
+// SAFETY: it owns values implementing the auto traits of `A`, so it's `Send`
+// and `Sync` the same way `Vec<Box<A>>` is
+unsafe impl<A: ?Sized + Send> Send for ErasedVec<A> {}
+// SAFETY: see above
+unsafe impl<A: ?Sized + Sync> Sync for ErasedVec<A> {}
+
+impl<A: ?Sized> fmt::Debug for ErasedVec<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedVec")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,166 @@
+use core::{
+    any::type_name,
+    error::Error,
+    fmt::{self, Debug, Display},
+};
+
+use crate::{mutable, owned, reference};
+
+/// Error returned by [`cismute::try_owned`](try_owned()) and friends when the
+/// types are different. Holds the original value and names both types when
+/// printed.
+///
+/// ```rust
+/// let err = cismute::try_owned::<_, i32>(String::from("hi")).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "expected `i32`, value was `alloc::string::String`"
+/// );
+/// assert_eq!(err.into_inner(), "hi");
+/// ```
+pub struct NotSame<T> {
+    value: T,
+    expected: &'static str,
+    found: &'static str,
+}
+
+impl<T> NotSame<T> {
+    #[inline(always)]
+    fn new<Found: ?Sized, Expected: ?Sized>(value: T) -> Self {
+        Self {
+            value,
+            expected: type_name::<Expected>(),
+            found: type_name::<Found>(),
+        }
+    }
+
+    /// Returns the original value.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Returns a reference to the original value.
+    #[inline(always)]
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the name of the type the value was cismuted to.
+    #[inline(always)]
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    /// Returns the name of the type of the value (or of the referenced value).
+    #[inline(always)]
+    pub fn found(&self) -> &'static str {
+        self.found
+    }
+}
+
+impl<T> Display for NotSame<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected `{}`, value was `{}`",
+            self.expected, self.found
+        )
+    }
+}
+
+// Doesn't require `T: Debug`, since the names are what matters
+impl<T> Debug for NotSame<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotSame")
+            .field("expected", &self.expected)
+            .field("found", &self.found)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Error for NotSame<T> {}
+
+/// Same as [`cismute::owned`](owned()), but the error describes both types.
+///
+/// ```rust
+/// fn parse<T: 'static>(val: T) -> Result<u32, String> {
+///     cismute::try_owned(val).map_err(|err| err.to_string())
+/// }
+///
+/// assert_eq!(parse(1_u32), Ok(1));
+/// assert_eq!(parse(1_i64).unwrap_err(), "expected `u32`, value was `i64`");
+/// ```
+#[inline(always)]
+pub fn try_owned<T: 'static, U: 'static>(val: T) -> Result<U, NotSame<T>> {
+    owned(val).map_err(NotSame::new::<T, U>)
+}
+
+/// Same as [`cismute::reference`](reference()), but the error describes both
+/// types.
+#[inline(always)]
+pub fn try_reference<T: ?Sized + 'static, U: ?Sized + 'static>(val: &T) -> Result<&U, NotSame<&T>> {
+    reference(val).map_err(NotSame::new::<T, U>)
+}
+
+/// Same as [`cismute::mutable`](mutable()), but the error describes both
+/// types.
+#[inline(always)]
+pub fn try_mutable<T: ?Sized + 'static, U: ?Sized + 'static>(
+    val: &mut T,
+) -> Result<&mut U, NotSame<&mut T>> {
+    mutable(val).map_err(NotSame::new::<T, U>)
+}
+
+/// Returns the error as `U` if `E` is the same type, e.g. to retry on a few
+/// known errors in code generic over the error type. Returns it back
+/// otherwise.
+///
+/// ```rust
+/// use std::{error::Error, fmt, io};
+///
+/// fn is_retryable<E: Error + 'static>(err: E) -> bool {
+///     match cismute::downcast_err_if_same::<io::Error, _>(err) {
+///         Ok(err) => err.kind() == io::ErrorKind::Interrupted,
+///         Err(_) => false,
+///     }
+/// }
+///
+/// assert!(is_retryable(io::Error::from(io::ErrorKind::Interrupted)));
+/// assert!(!is_retryable(fmt::Error));
+/// ```
+#[inline(always)]
+pub fn downcast_err_if_same<U: Error + 'static, E: Error + 'static>(err: E) -> Result<U, E> {
+    owned(err)
+}
+
+/// Returns a reference to the error as `&U` if `E` is the same type, like
+/// [`cismute::downcast_err_if_same`](downcast_err_if_same()).
+#[inline(always)]
+pub fn downcast_err_ref_if_same<U: Error + 'static, E: Error + ?Sized + 'static>(
+    err: &E,
+) -> Option<&U> {
+    reference(err).ok()
+}
+
+/// Returns the boxed error as `U` if it's of that type, e.g. for errors from
+/// crates that box them. Returns the box back otherwise. This function
+/// requires the `alloc` feature.
+///
+/// ```rust
+/// use std::{error::Error, fmt};
+///
+/// let err: Box<dyn Error + Send + Sync> = Box::new(fmt::Error);
+/// let err = cismute::downcast_boxed_err::<std::io::Error>(err).unwrap_err();
+/// assert_eq!(
+///     cismute::downcast_boxed_err::<fmt::Error>(err).unwrap(),
+///     fmt::Error
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[inline(always)]
+pub fn downcast_boxed_err<U: Error + 'static>(
+    err: alloc::boxed::Box<dyn Error + Send + Sync>,
+) -> Result<U, alloc::boxed::Box<dyn Error + Send + Sync>> {
+    err.downcast().map(|err| *err)
+}
@@ -0,0 +1,35 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Future returned by [`switch!()`](crate::switch!) with `async` arms. Holds
+/// either the future of the matched arm or a future of one of the following
+/// arms.
+#[derive(Debug)]
+pub enum SwitchFuture<First, Rest> {
+    /// Future of the first arm.
+    First(First),
+    /// Future of one of the following arms.
+    Rest(Rest),
+}
+
+impl<First, Rest> Future for SwitchFuture<First, Rest>
+where
+    First: Future,
+    Rest: Future<Output = First::Output>,
+{
+    type Output = First::Output;
+
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: the inner future is never moved out of the pinned enum
+        unsafe {
+            match self.get_unchecked_mut() {
+                Self::First(future) => Pin::new_unchecked(future).poll(cx),
+                Self::Rest(future) => Pin::new_unchecked(future).poll(cx),
+            }
+        }
+    }
+}
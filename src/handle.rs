@@ -0,0 +1,101 @@
+/// A handler for values of type `T`, for when a branch is better written as a
+/// named type than as a closure, e.g. to test it separately or to reuse it in
+/// several places. Use [`cismute::handler`](handler()) to pass it where a
+/// closure is expected.
+///
+/// ```rust
+/// use cismute::Handle;
+///
+/// #[derive(Default)]
+/// struct Stats {
+///     bytes: usize,
+///     strings: usize,
+/// }
+///
+/// impl Handle<u8> for Stats {
+///     type Output = ();
+///
+///     fn handle(&mut self, _: u8) {
+///         self.bytes += 1;
+///     }
+/// }
+///
+/// impl Handle<String> for Stats {
+///     type Output = ();
+///
+///     fn handle(&mut self, _: String) {
+///         self.strings += 1;
+///     }
+/// }
+///
+/// fn count<T: 'static>(stats: &mut Stats, val: T) {
+///     let _ = cismute::Switch::owned(val)
+///         .on(cismute::handler::<u8, _>(&mut *stats))
+///         .on(cismute::handler::<String, _>(&mut *stats))
+///         .finish();
+/// }
+///
+/// let mut stats = Stats::default();
+/// count(&mut stats, 1_u8);
+/// count(&mut stats, String::from("hi"));
+/// count(&mut stats, 'x');
+/// assert_eq!((stats.bytes, stats.strings), (1, 1));
+/// ```
+///
+/// Handlers for the same type can be stored as trait objects:
+///
+/// ```rust
+/// use cismute::Handle;
+///
+/// struct Double;
+///
+/// impl Handle<u32> for Double {
+///     type Output = u32;
+///
+///     fn handle(&mut self, val: u32) -> u32 {
+///         val * 2
+///     }
+/// }
+///
+/// let mut handlers: [Box<dyn Handle<u32, Output = u32>>; 2] =
+///     [Box::new(Double), Box::new(Double)];
+/// let result = handlers
+///     .iter_mut()
+///     .fold(1, |acc, handler| handler.handle(acc));
+/// assert_eq!(result, 4);
+/// ```
+pub trait Handle<T> {
+    /// What the handler returns.
+    type Output;
+
+    /// Handles the value.
+    fn handle(&mut self, val: T) -> Self::Output;
+}
+
+impl<T, H: Handle<T> + ?Sized> Handle<T> for &mut H {
+    type Output = H::Output;
+
+    #[inline(always)]
+    fn handle(&mut self, val: T) -> Self::Output {
+        (**self).handle(val)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, H: Handle<T> + ?Sized> Handle<T> for alloc::boxed::Box<H> {
+    type Output = H::Output;
+
+    #[inline(always)]
+    fn handle(&mut self, val: T) -> Self::Output {
+        (**self).handle(val)
+    }
+}
+
+/// Turns a [`Handle`] implementation into a closure, e.g. for the
+/// [`Switch`](crate::Switch) builder or [`switch()`](crate::switch()). Pass
+/// `&mut handler` to keep using it afterwards. If it handles several types,
+/// the type has to be specified: `cismute::handler::<T, _>(handler)`.
+#[inline(always)]
+pub fn handler<T, H: Handle<T>>(mut handler: H) -> impl FnMut(T) -> H::Output {
+    move |val| handler.handle(val)
+}
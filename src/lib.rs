@@ -1,4 +1,5 @@
 #![no_std]
+#![cfg_attr(feature = "nightly", feature(const_trait_impl, const_cmp))]
 // lint me harder
 #![forbid(non_ascii_idents)]
 #![deny(
@@ -8,7 +9,6 @@
     elided_lifetimes_in_paths,
     meta_variable_misuse,
     noop_method_call,
-    pointer_structural_match,
     unused_lifetimes,
     unused_qualifications,
     clippy::wildcard_dependencies,
@@ -75,7 +75,7 @@
 //! use cismute::Pair;
 //!
 //! fn specialized_function<T: 'static>(x: &T) -> String {
-//!     match cismute::value_with(Pair::<(T, i32)>, x) {
+//!     match cismute::value_with(Pair::<T, i32>::of(), x) {
 //!         Ok(x) => format!("got an i32: {x}"),
 //!         Err(x) => format!("got something else"),
 //!     }
@@ -85,137 +85,915 @@
 //! assert_eq!(specialized_function(&":)"), "got something else");
 //! ```
 //!
-//! There are also [`switch!()`] macro and [`switch()`] function
-//! to match one value with multiple types.
+//! There are also [`switch!()`] macro, [`switch()`] function and [`Switch`]
+//! builder to match one value with multiple types.
+//!
+//! [`TypeId`]s of generic parameters are known after monomorphization, so in
+//! optimized builds the comparison is folded into a constant and only one of
+//! the branches is left. There's no runtime branch to mark as cold or
+//! unlikely. [`JumpTable`] and [`DynBranches`] are the exception, since they
+//! choose the handler at runtime.
+//!
+//! Source types that aren't `'static` can still be matched with types that
+//! don't have lifetimes at all, see [`LifetimeFree`], or with types that
+//! declare their `'static` form, see [`Transient`].
+//!
+//! The `paranoid` feature additionally checks that matched types have the same
+//! layout and [`type_name`](core::any::type_name()), and aborts otherwise, in
+//! case [`TypeId`]s ever collide. For references, layouts of the referenced
+//! values are compared. Names and referenced values aren't checked in
+//! `const fn`s with the `nightly` feature.
+//!
+//! The `strict` feature makes types with the same [`TypeId`] but different
+//! names count as different types instead, so a collision falls back to the
+//! mismatch branch. Name comparison is usually folded too, but may cost a few
+//! cycles in debug builds. With the `nightly` feature, names can't be compared
+//! in const context, so only the lookups done at runtime respect `strict`:
+//! [`JumpTable`], `Registry`, [`AnyRef`] and [`AnyMut`], and casts of
+//! [`LifetimeFree`] types. Everything else, including
+//! [`cismute::value`](value()), [`switch!()`](switch!) and [`TypeEq`], only
+//! compares [`TypeId`]s then.
+//!
+//! With the `nightly` feature, type comparisons are done in const context,
+//! so mismatched branches are removed even in debug builds, and the core
+//! functions are `const fn`s.
+//!
+//! Everything is `#[inline(always)]`, which is best for speed. With the
+//! `compact` feature, dispatch helpers (switches, jump tables and registries)
+//! are left to the optimizer instead, so code switching on the same types in
+//! many places can share them, e.g. with `opt-level = "z"`.
+//!
+//! The `stats` feature counts matches and mismatches per call site, see
+//! [`stats`]. It records nothing together with the `nightly` feature, since
+//! [`cismute::value`](value()) is a `const fn` there.
+//!
+//! The minimum supported Rust version is 1.91, since [`Handler`]s are created
+//! in const context.
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "paranoid")]
+use core::alloc::Layout;
+use core::{
+    any::TypeId,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ptr,
+};
+
+// Makes functions `const` with the `nightly` feature, since `TypeId`s can't be
+// compared in const context on stable yet. Defined before the modules, so
+// they can use it too
+macro_rules! nightly_const {
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        #[cfg(feature = "nightly")]
+        $(#[$attr])*
+        $vis const fn $($rest)*
+
+        #[cfg(not(feature = "nightly"))]
+        $(#[$attr])*
+        $vis fn $($rest)*
+    };
+}
+
+pub mod prelude;
+
+#[cfg(feature = "stats")]
+pub mod stats;
+
+#[cfg(feature = "castaway")]
+pub mod castaway;
+
+mod any;
+#[cfg(feature = "alloc")]
+pub use any::downcast_box;
+pub use any::{downcast_mut, downcast_ref, AnyMut, AnyRef, AnySlice, AnySliceMut, AutoTraits};
+
+mod builder;
+pub use builder::Switch;
+
+mod handle;
+pub use handle::{handler, Handle};
+
+mod table;
+pub use table::{Handler, JumpTable, Resolved};
+
+#[cfg(feature = "alloc")]
+mod registry;
+#[cfg(feature = "alloc")]
+pub use registry::Registry;
+
+mod future;
+pub use future::SwitchFuture;
+
+mod bytes;
+pub use bytes::{as_bytes_if_u8, as_bytes_mut_if_u8};
+#[cfg(feature = "alloc")]
+pub use bytes::{extend_specialized, into_bytes_if_u8};
+
+mod cmp;
+pub use cmp::{cmp_if_same, eq_if_same, hash_if_same};
+
+#[cfg(feature = "alloc")]
+mod erased_vec;
+#[cfg(feature = "alloc")]
+pub use erased_vec::ErasedVec;
+
+mod error;
+#[cfg(feature = "alloc")]
+pub use error::downcast_boxed_err;
+pub use error::{
+    downcast_err_if_same, downcast_err_ref_if_same, try_mutable, try_owned, try_reference, NotSame,
+};
+
+mod one_of;
+pub use one_of::{HandleOneOf, OneOf};
+
+mod same_as;
+pub use same_as::SameAs;
+
+mod slot;
+pub use slot::{
+    clone_from_if_same, copy_from_if_same, replace_if_same, set_if_same, swap_if_same,
+    uninit_write_if_same,
+};
+
+mod small_any;
+pub use small_any::SmallAny;
+
+mod specialized;
+pub use specialized::{specialize, Specialized};
 
-use core::{any::TypeId, marker::PhantomData, mem::ManuallyDrop};
+mod type_map;
+pub use type_map::TypeMap;
+
+mod type_set;
+pub use type_set::{Cons, Nil, TypeSet, VisitType};
+
+mod type_eq;
+pub use type_eq::{value_proved, with_proof, TypeEq};
+
+mod transparent;
+pub use transparent::{unwrap, wrap, Transparent};
+
+mod lifetime_free;
+pub use lifetime_free::{
+    mutable_branded, mutable_lifetime_free, owned_branded, owned_lifetime_free, owned_transient,
+    reference_branded, reference_lifetime_free, reference_transient, Branded, LifetimeFree,
+    Transient,
+};
 
 #[cfg(feature = "switch")]
 mod branches;
 
+#[cfg(feature = "switch")]
+#[doc(hidden)]
+pub use branches::__position;
 #[cfg(feature = "switch")]
 use branches::Branches;
+#[cfg(feature = "switch")]
+pub use branches::{DynBranches, Erased};
+
+// Required because transmute doesn't work in generic contexts. Reading through
+// a pointer avoids copying the value into a union first, which isn't always
+// optimized out for large values
+#[inline(always)]
+const unsafe fn generic_transmute<T, U>(from: T) -> U {
+    #[cfg(feature = "paranoid")]
+    if size_of::<T>() != size_of::<U>() || align_of::<T>() != align_of::<U>() {
+        paranoid_abort("cismute: layouts of the types don't match");
+    }
+    let from = ManuallyDrop::new(from);
+    // SAFETY: `from` is never dropped, so the value is moved exactly once; the
+    // caller guarantees that it's a valid `U`
+    ptr::read(ptr::from_ref::<ManuallyDrop<T>>(&from).cast::<U>())
+}
+
+// Panicking in a function that can't unwind aborts, and `core` has no other way
+// to abort. The message is still printed by the panic handler
+#[cfg(feature = "paranoid")]
+// it's never called from C
+#[allow(improper_ctypes_definitions, clippy::panic)]
+#[cold]
+#[inline(never)]
+const extern "C" fn paranoid_abort(message: &'static str) -> ! {
+    panic!("{}", message)
+}
+
+// References always have the same layout, so `paranoid` compares the values
+// they point to
+#[cfg(feature = "paranoid")]
+#[inline(always)]
+fn check_pointee_layouts(from: Option<Layout>, to: Option<Layout>) {
+    if from != to {
+        paranoid_abort("cismute: layouts of the referenced values don't match");
+    }
+}
 
-#[repr(C)]
-union GenericTransmute<T, U> {
-    from: ManuallyDrop<T>,
-    to: ManuallyDrop<U>,
+// On nightly the comparison is done in an inline `const`, so the branch is
+// resolved on monomorphization even without optimizations
+#[cfg(feature = "nightly")]
+#[inline(always)]
+const fn same_type<T: ?Sized + 'static, U: ?Sized + 'static>() -> bool {
+    const { TypeId::of::<T>() == TypeId::of::<U>() }
+}
+
+#[cfg(not(feature = "nightly"))]
+#[inline(always)]
+fn same_type<T: ?Sized + 'static, U: ?Sized + 'static>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<U>() && (!cfg!(feature = "strict") || same_name::<T, U>())
 }
 
-// Required because transmute doesn't work in generic contexts
+// Names aren't unique, but types with different names are definitely different
 #[inline(always)]
-unsafe fn generic_transmute<T, U>(from: T) -> U {
-    ManuallyDrop::into_inner(
-        GenericTransmute {
-            from: ManuallyDrop::new(from),
+fn same_name<T: ?Sized, U: ?Sized>() -> bool {
+    core::any::type_name::<T>() == core::any::type_name::<U>()
+}
+
+nightly_const! {
+    // Same as `same_type()`, but with `paranoid` aborts if types with the same
+    // `TypeId` have different names. Everything that acts on the result goes
+    // through it, so the check can't be skipped
+    #[inline(always)]
+    fn checked_same_type<T: ?Sized + 'static, U: ?Sized + 'static>() -> bool {
+        let same = same_type::<T, U>();
+        // `type_name()` isn't const yet
+        #[cfg(all(feature = "paranoid", not(feature = "nightly")))]
+        if same && !same_name::<T, U>() {
+            paranoid_abort("cismute: types with the same `TypeId` have different names");
         }
-        .to,
-    )
+        same
+    }
 }
 
 /// Pair of two types for passing to [`cismute::value_with`](value_with()).
-///
-/// <!-- I'm sorry, but rustdoc does something weird with reexports -->
-/// <div style="display: none">
-pub use core::marker::PhantomData as Pair;
+/// Create it with [`Pair::of()`].
+pub struct Pair<T, U>(PhantomData<fn() -> (T, U)>);
+
+impl<T, U> Pair<T, U> {
+    /// Creates a pair of types `T` and `U`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn of() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, U> Clone for Pair<T, U> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, U> Copy for Pair<T, U> {}
+
+impl<T, U> Default for Pair<T, U> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::of()
+    }
+}
+
+impl<T, U> core::fmt::Debug for Pair<T, U> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Pair<{}, {}>",
+            core::any::type_name::<T>(),
+            core::any::type_name::<U>(),
+        )
+    }
+}
 
 /// A reference that can be safely transmuted if underlying type is the same.
 ///
 /// # Safety
 /// For any `RefT: Cismutable<'a, T, RefU>` transmutation from `RefT` to
 /// `RefU` must be safe if `T` and `U` are the same type.
-pub unsafe trait Cismutable<'a, T, U, RefU> {}
-unsafe impl<'a, T, U> Cismutable<'a, T, U, &'a U> for &'a T {}
-unsafe impl<'a, T, U> Cismutable<'a, T, U, &'a mut U> for &'a mut T {}
+pub unsafe trait Cismutable<'a, T: ?Sized, U: ?Sized, RefU> {
+    // Layout of the referenced value, for the `paranoid` checks
+    #[cfg(feature = "paranoid")]
+    #[doc(hidden)]
+    #[inline(always)]
+    fn __pointee_layout(&self) -> Option<Layout> {
+        None
+    }
+
+    #[cfg(feature = "paranoid")]
+    #[doc(hidden)]
+    #[inline(always)]
+    fn __target_pointee_layout(_target: &RefU) -> Option<Layout>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+unsafe impl<'a, T: ?Sized, U: ?Sized> Cismutable<'a, T, U, &'a U> for &'a T {
+    #[cfg(feature = "paranoid")]
+    #[inline(always)]
+    fn __pointee_layout(&self) -> Option<Layout> {
+        Some(Layout::for_value(*self))
+    }
+
+    #[cfg(feature = "paranoid")]
+    #[inline(always)]
+    fn __target_pointee_layout(target: &&'a U) -> Option<Layout> {
+        Some(Layout::for_value(*target))
+    }
+}
+
+unsafe impl<'a, T: ?Sized, U: ?Sized> Cismutable<'a, T, U, &'a mut U> for &'a mut T {
+    #[cfg(feature = "paranoid")]
+    #[inline(always)]
+    fn __pointee_layout(&self) -> Option<Layout> {
+        Some(Layout::for_value(&**self))
+    }
+
+    #[cfg(feature = "paranoid")]
+    #[inline(always)]
+    fn __target_pointee_layout(target: &&'a mut U) -> Option<Layout> {
+        Some(Layout::for_value(&**target))
+    }
+}
+
 unsafe impl<T: 'static, U: 'static> Cismutable<'static, T, U, U> for T {}
 
 mod seal {
     pub trait Phantom<T> {}
+
+    // Maps `RefT` to `RefU` for a given `U`, so the latter can be inferred
+    pub trait Retype<'a, T: ?Sized, U: ?Sized> {
+        type Output;
+    }
 }
 use seal::Phantom;
 
 impl<T> Phantom<T> for PhantomData<T> {}
+impl<T, U> Phantom<(T, U)> for Pair<T, U> {}
+
+impl<'a, T: ?Sized, U: ?Sized + 'static> seal::Retype<'a, T, U> for &'a T {
+    type Output = &'a U;
+}
+impl<'a, T: ?Sized, U: ?Sized + 'static> seal::Retype<'a, T, U> for &'a mut T {
+    type Output = &'a mut U;
+}
+impl<T: 'static, U: 'static> seal::Retype<'static, T, U> for T {
+    type Output = U;
+}
+
+nightly_const! {
+    /// Returns `true` if `T` and `U` are the same type. Like the other
+    /// functions, it's folded into a constant in optimized builds, and is a
+    /// `const fn` with the `nightly` feature.
+    ///
+    /// ```rust
+    /// fn chunk_size<T: 'static>() -> usize {
+    ///     if cismute::same::<T, u8>() {
+    ///         4096
+    ///     } else {
+    ///         64
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(chunk_size::<u8>(), 4096);
+    /// assert_eq!(chunk_size::<String>(), 64);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn same<T, U>() -> bool
+    where
+        T: ?Sized + 'static,
+        U: ?Sized + 'static,
+    {
+        checked_same_type::<T, U>()
+    }
+}
+
+nightly_const! {
+    /// Returns `true` if [`TypeId`] of `T` is in `ids`. Use
+    /// [`type_ids!()`](type_ids!) to build the list, which can be a constant
+    /// shared by several checks.
+    ///
+    /// ```rust
+    /// use std::any::TypeId;
+    ///
+    /// const INTEGERS: &[TypeId] = &cismute::type_ids![u8, u16, u32, u64, u128, usize];
+    ///
+    /// fn describe<T: 'static>(_: T) -> &'static str {
+    ///     if cismute::is_any_of::<T>(INTEGERS) {
+    ///         "an integer"
+    ///     } else {
+    ///         "something else"
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(describe(42_u32), "an integer");
+    /// assert_eq!(describe(4.2_f32), "something else");
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn is_any_of<T: ?Sized + 'static>(ids: &[TypeId]) -> bool {
+        let id = TypeId::of::<T>();
+        // iterators aren't const
+        let mut i = 0;
+        while i < ids.len() {
+            if ids[i] == id {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+}
+
+nightly_const! {
+    /// Transmutes an owned value of type `T` to type `U` if they are the same
+    /// type. Returns the passed value back if failed.
+    ///
+    /// See module-level docs for usage example.
+    #[inline(always)]
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn owned<T, U>(val: T) -> Result<U, T>
+    where
+        T: 'static,
+        U: 'static,
+    {
+        value(val)
+    }
+}
+
+nightly_const! {
+    /// Transmutes reference to type `T` to `&U` if they are the same type.
+    /// Returns the passed value back if failed.
+    ///
+    /// Both types can be unsized, so e.g. `&T` can be matched with `&str`:
+    ///
+    /// ```rust
+    /// fn byte_len<T: ?Sized + 'static>(val: &T) -> Option<usize> {
+    ///     match cismute::reference::<T, str>(val) {
+    ///         Ok(s) => Some(s.len()),
+    ///         Err(val) => cismute::reference::<T, [u8]>(val).ok().map(<[u8]>::len),
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(byte_len("hi"), Some(2));
+    /// assert_eq!(byte_len(&b"bytes"[..]), Some(5));
+    /// assert_eq!(byte_len(&[1_u32, 2][..]), None);
+    /// ```
+    #[inline(always)]
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn reference<'a, T, U>(val: &'a T) -> Result<&'a U, &'a T>
+    where
+        T: ?Sized + 'static,
+        U: ?Sized + 'static,
+    {
+        value::<'a, T, U, _, _>(val)
+    }
+}
+
+nightly_const! {
+    /// Transmutes a mutable reference to type `T` to `&mut U` if they are the
+    /// same type. Returns the passed value back if failed.
+    ///
+    /// See module-level docs for usage example.
+    #[inline(always)]
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn mutable<'a, T, U>(val: &'a mut T) -> Result<&'a mut U, &'a mut T>
+    where
+        T: ?Sized + 'static,
+        U: ?Sized + 'static,
+    {
+        value::<'a, T, U, _, _>(val)
+    }
+}
+
+nightly_const! {
+    /// Cismutes `T` or a (possibly mutable) reference to `T` to `U` with the
+    /// same ownership, i.e. owned value is cismuted like with
+    /// [`cismute::owned`](owned()), reference is cismuted to reference and
+    /// mutable reference is cismuted to mutable reference, if they are the same
+    /// type. Returns the passed value back if failed.
+    ///
+    /// See module-level docs for usage example. With the `nightly` feature,
+    /// this function and its variants are `const`:
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "nightly")] {
+    /// const fn is_byte<T: Copy + 'static>(val: T) -> bool {
+    ///     cismute::owned::<T, u8>(val).is_ok()
+    /// }
+    ///
+    /// const BYTE: bool = is_byte(1_u8);
+    /// const NOT_BYTE: bool = is_byte(1_u32);
+    /// assert!(BYTE && !NOT_BYTE);
+    /// # }
+    /// ```
+    ///
+    /// With the `paranoid` feature, aborts if `T` and `U` have the same
+    /// [`TypeId`], but different names or layouts, which should never happen.
+    #[inline(always)]
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn value<'a, T, U, RefT, RefU>(val: RefT) -> Result<RefU, RefT>
+    where
+        T: ?Sized + 'static,
+        U: ?Sized + 'static,
+        RefT: Cismutable<'a, T, U, RefU>,
+    {
+        let same = checked_same_type::<T, U>();
+        #[cfg(all(feature = "stats", not(feature = "nightly")))]
+        stats::record(core::panic::Location::caller(), same);
+        if same {
+            // Trait methods aren't const yet
+            #[cfg(all(feature = "paranoid", not(feature = "nightly")))]
+            let layout = val.__pointee_layout();
+            // SAFETY: T and U are the same type
+            let val = unsafe { generic_transmute::<RefT, RefU>(val) };
+            #[cfg(all(feature = "paranoid", not(feature = "nightly")))]
+            check_pointee_layouts(
+                layout,
+                <RefT as Cismutable<'a, T, U, RefU>>::__target_pointee_layout(&val),
+            );
+            Ok(val)
+        } else {
+            Err(val)
+        }
+    }
+}
+
+nightly_const! {
+    /// Cismutes `T` or a (possibly mutable) reference to `T` to the type
+    /// specified by the first argument while preserving ownership, i.e. owned
+    /// value is cismuted like with [`cismute::owned`](owned()), reference is
+    /// cismuted to reference and mutable reference is cismuted to mutable
+    /// reference. Returns the passed value back if failed.
+    ///
+    /// **Note**: first argument specifies types `T` and `U`, not the reference
+    /// types.
+    ///
+    /// See module-level docs for usage example.
+    #[inline(always)]
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn value_with<'a, T, U, P, RefT, RefU>(pair: P, val: RefT) -> Result<RefU, RefT>
+    where
+        T: 'static,
+        U: 'static,
+        RefT: Cismutable<'a, T, U, RefU>,
+        P: Phantom<(T, U)>,
+    {
+        // it's a marker, but const fns can't drop generic values
+        mem::forget(pair);
+        value::<T, U, RefT, RefU>(val)
+    }
+}
+
+// Used by functions that are documented to panic
+#[allow(clippy::panic)]
+#[cold]
+#[track_caller]
+fn mismatch<T: ?Sized, U: ?Sized>() -> ! {
+    panic!(
+        "cismute: `{}` and `{}` are different types",
+        core::any::type_name::<T>(),
+        core::any::type_name::<U>(),
+    )
+}
 
-/// Transmutes an owned value of type `T` to type `U` if they are the same type.
-/// Returns the passed value back if failed.
+/// Same as [`cismute::value`](value()), but panics if the types are
+/// different, for when they are known to be the same.
+///
+/// ```rust
+/// fn bump<T: 'static>(val: &mut T) {
+///     *cismute::expect_value::<T, u32, _, _>(val) += 1;
+/// }
 ///
-/// See module-level docs for usage example.
+/// let mut x = 1_u32;
+/// bump(&mut x);
+/// assert_eq!(x, 2);
+/// ```
+///
+/// # Panics
+/// Panics if `T` and `U` are different types. The message contains both type
+/// names and the location of the caller.
 #[inline(always)]
-pub fn owned<T, U>(val: T) -> Result<U, T>
+#[track_caller]
+pub fn expect_value<'a, T, U, RefT, RefU>(val: RefT) -> RefU
 where
-    T: 'static,
-    U: 'static,
+    T: ?Sized + 'static,
+    U: ?Sized + 'static,
+    RefT: Cismutable<'a, T, U, RefU>,
 {
-    value(val)
+    match value(val) {
+        Ok(val) => val,
+        Err(_) => mismatch::<T, U>(),
+    }
+}
+
+/// Same as [`cismute::owned`](owned()), but panics if the types are different.
+///
+/// ```rust,should_panic
+/// fn into_string<T: 'static>(val: T) -> String {
+///     cismute::expect_owned(val)
+/// }
+///
+/// assert_eq!(into_string(String::from("hi")), "hi");
+/// // panics with "cismute: `&str` and `alloc::string::String` are different types"
+/// into_string("hi");
+/// ```
+///
+/// # Panics
+/// Panics if `T` and `U` are different types.
+#[inline(always)]
+#[track_caller]
+pub fn expect_owned<T: 'static, U: 'static>(val: T) -> U {
+    expect_value::<T, U, _, _>(val)
 }
 
-/// Transmutes reference to type `T` to `&U` if they are the same type.
-/// Returns the passed value back if failed.
+/// Same as [`cismute::reference`](reference()), but panics if the types are
+/// different.
 ///
-/// See module-level docs for usage example.
+/// # Panics
+/// Panics if `T` and `U` are different types.
 #[inline(always)]
-pub fn reference<'a, T, U>(val: &'a T) -> Result<&'a U, &'a T>
-where
-    T: 'static,
-    U: 'static,
-{
-    value::<'a, T, U, _, _>(val)
+#[track_caller]
+pub fn expect_reference<T: ?Sized + 'static, U: ?Sized + 'static>(val: &T) -> &U {
+    expect_value::<T, U, _, _>(val)
 }
 
-/// Transmutes a mutable reference to type `T` to `&mut U` if they are the same
-/// type. Returns the passed value back if failed.
+/// Same as [`cismute::mutable`](mutable()), but panics if the types are
+/// different.
 ///
-/// See module-level docs for usage example.
+/// # Panics
+/// Panics if `T` and `U` are different types.
 #[inline(always)]
-pub fn mutable<'a, T, U>(val: &'a mut T) -> Result<&'a mut U, &'a mut T>
-where
-    T: 'static,
-    U: 'static,
-{
-    value::<'a, T, U, _, _>(val)
+#[track_caller]
+pub fn expect_mutable<T: ?Sized + 'static, U: ?Sized + 'static>(val: &mut T) -> &mut U {
+    expect_value::<T, U, _, _>(val)
 }
 
-/// Cismutes `T` or a (possibly mutable) reference to `T` to `U` with the same
-/// ownership, i.e. owned value is cismuted like with
-/// [`cismute::owned`](owned()), reference is cismuted to reference and mutable
-/// reference is cismuted to mutable reference, if they are the same type.
-/// Returns the passed value back if failed.
+/// Returns a closure that cismutes an owned value to `U`, like
+/// [`cismute::owned`](owned()). `T` is usually inferred, so it can be written
+/// as `cismute::to::<U, _>()`.
 ///
-/// See module-level docs for usage example.
+/// ```rust
+/// fn as_byte<T: 'static>(val: Option<T>) -> Option<u8> {
+///     val.map(cismute::to::<u8, _>()).and_then(Result::ok)
+/// }
+///
+/// assert_eq!(as_byte(Some(1_u8)), Some(1));
+/// assert_eq!(as_byte(Some(1_i8)), None);
+/// ```
 #[inline(always)]
-pub fn value<'a, T, U, RefT, RefU>(val: RefT) -> Result<RefU, RefT>
-where
-    T: 'static,
-    U: 'static,
-    RefT: Cismutable<'a, T, U, RefU>,
-{
-    if TypeId::of::<T>() == TypeId::of::<U>() {
-        // SAFETY: T and U are the same type
-        Ok(unsafe { generic_transmute::<RefT, RefU>(val) })
-    } else {
-        Err(val)
+pub fn to<U: 'static, T: 'static>() -> impl Fn(T) -> Result<U, T> + Copy {
+    owned::<T, U>
+}
+
+/// Same as [`cismute::to`](to()), but for references, like
+/// [`cismute::reference`](reference()).
+///
+/// ```rust
+/// fn find_str<T: 'static>(items: &[T]) -> Option<&str> {
+///     items
+///         .iter()
+///         .map(cismute::to_ref::<String, _>())
+///         .find_map(Result::ok)
+///         .map(String::as_str)
+/// }
+///
+/// assert_eq!(find_str(&[String::from("hi")]), Some("hi"));
+/// assert_eq!(find_str(&[1, 2]), None);
+/// ```
+#[inline(always)]
+pub fn to_ref<U: ?Sized + 'static, T: ?Sized + 'static>(
+) -> impl for<'a> Fn(&'a T) -> Result<&'a U, &'a T> + Copy {
+    reference::<T, U>
+}
+
+/// Same as [`cismute::to`](to()), but for mutable references, like
+/// [`cismute::mutable`](mutable()).
+///
+/// ```rust
+/// fn clear_strings<T: 'static>(items: &mut [T]) {
+///     items
+///         .iter_mut()
+///         .map(cismute::to_mut::<String, _>())
+///         .filter_map(Result::ok)
+///         .for_each(String::clear);
+/// }
+///
+/// let mut strings = [String::from("hi")];
+/// clear_strings(&mut strings);
+/// assert_eq!(strings, [""]);
+/// ```
+#[inline(always)]
+pub fn to_mut<U: ?Sized + 'static, T: ?Sized + 'static>(
+) -> impl for<'a> Fn(&'a mut T) -> Result<&'a mut U, &'a mut T> + Copy {
+    mutable::<T, U>
+}
+
+/// Transmutes an owned value of type `T` to type `U` if they are the same
+/// type, otherwise drops it and returns `default`.
+///
+/// ```rust
+/// fn id_or_zero<T: 'static>(val: T) -> u64 {
+///     cismute::owned_or(val, 0)
+/// }
+///
+/// assert_eq!(id_or_zero(42_u64), 42);
+/// assert_eq!(id_or_zero("42"), 0);
+/// ```
+#[inline(always)]
+pub fn owned_or<T: 'static, U: 'static>(val: T, default: U) -> U {
+    owned(val).unwrap_or(default)
+}
+
+/// Transmutes an owned value of type `T` to type `U` if they are the same
+/// type, otherwise converts it with `convert`.
+///
+/// ```rust
+/// fn to_string<T: ToString + 'static>(val: T) -> String {
+///     // no need to copy the string if it's already a `String`
+///     cismute::owned_or_else(val, |val| val.to_string())
+/// }
+///
+/// assert_eq!(to_string(String::from("hi")), "hi");
+/// assert_eq!(to_string(42), "42");
+/// ```
+#[inline(always)]
+pub fn owned_or_else<T: 'static, U: 'static>(val: T, convert: impl FnOnce(T) -> U) -> U {
+    owned(val).unwrap_or_else(convert)
+}
+
+/// Transmutes an owned value of type `T` to type `U` if they are the same
+/// type, otherwise drops it and returns [`U::default()`](Default::default()).
+///
+/// ```rust
+/// fn name<T: 'static>(val: T) -> String {
+///     cismute::owned_or_default(val)
+/// }
+///
+/// assert_eq!(name(String::from("Ferris")), "Ferris");
+/// assert_eq!(name(42), "");
+/// ```
+#[inline(always)]
+pub fn owned_or_default<T: 'static, U: Default + 'static>(val: T) -> U {
+    owned(val).unwrap_or_default()
+}
+
+nightly_const! {
+    /// Transmutes two values of type `T` to `U` if they are the same type, with
+    /// one type check, e.g. for both operands of a binary operation. Returns
+    /// the passed values back if failed.
+    ///
+    /// ```rust
+    /// fn add<T: Copy + 'static>(a: T, b: T) -> Option<u64> {
+    ///     let (a, b) = cismute::both::<T, u32>((a, b)).ok()?;
+    ///     Some(u64::from(a) + u64::from(b))
+    /// }
+    ///
+    /// assert_eq!(add(u32::MAX, 1), Some(1 << 32));
+    /// assert_eq!(add(1_u8, 2), None);
+    /// ```
+    ///
+    /// See [`cismute::all`](all()) for arrays, and slices can be matched with
+    /// [`cismute::reference::<[T], [U]>`](reference()).
+    #[inline(always)]
+    pub fn both<T, U>(vals: (T, T)) -> Result<(U, U), (T, T)>
+    where
+        T: 'static,
+        U: 'static,
+    {
+        owned::<(T, T), (U, U)>(vals)
+    }
+}
+
+nightly_const! {
+    /// Transmutes an array of `T`s to an array of `U`s if they are the same
+    /// type, with one type check. Returns the passed array back if failed.
+    ///
+    /// ```rust
+    /// fn sum<T: Copy + 'static, const N: usize>(vals: [T; N]) -> Option<u32> {
+    ///     cismute::all::<T, u32, N>(vals).ok().map(|vals| vals.iter().sum())
+    /// }
+    ///
+    /// assert_eq!(sum([1_u32, 2, 3]), Some(6));
+    /// assert_eq!(sum([1_u8, 2, 3]), None);
+    /// ```
+    #[inline(always)]
+    pub fn all<T, U, const N: usize>(vals: [T; N]) -> Result<[U; N], [T; N]>
+    where
+        T: 'static,
+        U: 'static,
+    {
+        owned::<[T; N], [U; N]>(vals)
+    }
+}
+
+nightly_const! {
+    /// Transmutes `T1` to `U1` and `T2` to `U2` if both pairs are the same
+    /// types. Returns the passed values back if any of them isn't, e.g. to
+    /// specialize a function with two generic parameters without nested
+    /// matches:
+    ///
+    /// ```rust
+    /// fn describe<K: 'static, V: 'static>(key: K, val: V) -> String {
+    ///     match cismute::owned2::<K, String, V, u32>((key, val)) {
+    ///         Ok((key, val)) => format!("{key} = {val}"),
+    ///         Err(_) => String::from("something else"),
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(describe(String::from("x"), 42_u32), "x = 42");
+    /// assert_eq!(describe(String::from("x"), 42_i32), "something else");
+    /// ```
+    #[inline(always)]
+    pub fn owned2<T1, U1, T2, U2>(vals: (T1, T2)) -> Result<(U1, U2), (T1, T2)>
+    where
+        T1: 'static,
+        U1: 'static,
+        T2: 'static,
+        U2: 'static,
+    {
+        owned::<(T1, T2), (U1, U2)>(vals)
+    }
+}
+
+nightly_const! {
+    /// Same as [`cismute::owned2`](owned2()), but for three values.
+    #[inline(always)]
+    pub fn owned3<T1, U1, T2, U2, T3, U3>(
+        vals: (T1, T2, T3),
+    ) -> Result<(U1, U2, U3), (T1, T2, T3)>
+    where
+        T1: 'static,
+        U1: 'static,
+        T2: 'static,
+        U2: 'static,
+        T3: 'static,
+        U3: 'static,
+    {
+        owned::<(T1, T2, T3), (U1, U2, U3)>(vals)
     }
 }
 
-/// Cismutes `T` or a (possibly mutable) reference to `T` to the type specified
-/// by the first argument while preserving ownership, i.e. owned value is
-/// cismuted like with [`cismute::owned`](owned()), reference is cismuted to
-/// reference and mutable reference is cismuted to mutable reference.
-/// Returns the passed value back if failed.
+/// Calls `fast` with the value transmuted to `U` if `T` and `U` are the same
+/// type, and `generic` with the original value otherwise.
 ///
-/// **Note**: first argument specifies types `T` and `U`, not the reference
-/// types.
+/// For references or more than one fast path, use the [`Switch`] builder.
+///
+/// ```rust
+/// fn sum<T: Copy + Into<u64> + 'static>(items: Vec<T>) -> u64 {
+///     cismute::map_specialized(
+///         items,
+///         |bytes: Vec<u8>| bytes.iter().map(|&x| u64::from(x)).sum(),
+///         |items| items.into_iter().map(Into::into).sum(),
+///     )
+/// }
 ///
-/// See module-level docs for usage example.
+/// assert_eq!(sum(vec![1_u8, 2]), 3);
+/// assert_eq!(sum(vec![1_u32, 2]), 3);
+/// ```
 #[inline(always)]
-pub fn value_with<'a, T, U, P, RefT, RefU>(_: P, val: RefT) -> Result<RefU, RefT>
-where
-    T: 'static,
-    U: 'static,
-    RefT: Cismutable<'a, T, U, RefU>,
-    P: Phantom<(T, U)>,
-{
-    value::<T, U, RefT, RefU>(val)
+pub fn map_specialized<T: 'static, U: 'static, R>(
+    val: T,
+    fast: impl FnOnce(U) -> R,
+    generic: impl FnOnce(T) -> R,
+) -> R {
+    match owned(val) {
+        Ok(val) => fast(val),
+        Err(val) => generic(val),
+    }
+}
+
+/// Calls `f` with the slice retyped to `&mut [U]` if `T` and `U` are the same
+/// type, e.g. to run an existing routine for a concrete type on a generic
+/// buffer. The retyped slice can't escape the closure. Returns `None` if the
+/// types are different.
+///
+/// ```rust
+/// fn double_bytes(bytes: &mut [u8]) -> usize {
+///     bytes.iter_mut().for_each(|x| *x = x.wrapping_mul(2));
+///     bytes.len()
+/// }
+///
+/// fn double<T: 'static>(items: &mut [T]) -> Option<usize> {
+///     cismute::with_slice_as(items, double_bytes)
+/// }
+///
+/// let mut bytes = [1_u8, 2];
+/// assert_eq!(double(&mut bytes), Some(2));
+/// assert_eq!(bytes, [2, 4]);
+/// assert_eq!(double(&mut [1_u16]), None);
+/// ```
+#[inline(always)]
+pub fn with_slice_as<T: 'static, U: 'static, R>(
+    val: &mut [T],
+    f: impl FnOnce(&mut [U]) -> R,
+) -> Option<R> {
+    mutable::<[T], [U]>(val).ok().map(f)
 }
 
 /// Try to match `T` with several (up to 32) other types. This function requires
@@ -239,8 +1017,10 @@ where
 /// assert_eq!(specialized_function([1, 2]), "got something else: [1, 2]");
 /// ```
 ///
-/// Switching on references requires specifying the source type. This is a
-/// limitation of Rust type system.
+/// Switching on references requires specifying the source type, since a
+/// reference is a `'static` value too. This is a limitation of Rust type
+/// system. [`switch_reference()`] and [`switch_mutable()`] don't have this
+/// problem.
 ///
 /// ```rust
 /// # use std::fmt::Debug;
@@ -267,7 +1047,12 @@ where
 ///     "got something else: [1, 2]"
 /// );
 /// ```
-#[inline(always)]
+///
+/// Branches are tried in order, which is folded after monomorphization in
+/// optimized builds. Without optimizations or with the `compact` feature they
+/// are compared one by one, so for large sets of types [`JumpTable`], which
+/// does a binary search over handlers sorted by [`TypeId`], may be faster.
+#[cfg_attr(not(feature = "compact"), inline(always))]
 #[cfg(feature = "switch")]
 pub fn switch<R, T, RefT, Args, Tuple>(val: RefT, branches: Tuple) -> Result<R, RefT>
 where
@@ -276,35 +1061,247 @@ where
     branches.dispatch(val)
 }
 
-/// Helper function for [`switch()`].
-#[inline(always)]
+/// Same as [`switch()`], but for references. The source type is inferred, so
+/// there's no need for [`from()`]. This function requires the `switch`
+/// feature.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// fn specialized_function<T: Debug + 'static>(val: &T) -> String {
+///     cismute::switch_reference(
+///         val,
+///         (
+///             |x: &i32| format!("got an i32: {x}"),
+///             |x: &char| format!("got a char: {x}"),
+///         ),
+///     )
+///     .unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// assert_eq!(specialized_function(&42_i32), "got an i32: 42");
+/// assert_eq!(specialized_function(&'!'), "got a char: !");
+/// assert_eq!(specialized_function(&[1, 2]), "got something else: [1, 2]");
+/// ```
+#[cfg_attr(not(feature = "compact"), inline(always))]
 #[cfg(feature = "switch")]
-pub fn from<R, T, P, RefT, Args, Tuple>(_: P, branches: Tuple) -> impl Branches<R, T, RefT, Args>
+pub fn switch_reference<'a, R, T, Args, Tuple>(val: &'a T, branches: Tuple) -> Result<R, &'a T>
 where
-    P: Phantom<T>,
-    Tuple: Branches<R, T, RefT, Args>,
+    Tuple: Branches<R, T, &'a T, Args>,
 {
-    branches
+    branches.dispatch(val)
 }
 
-/// Try to match a value with any number of types. This macro _does not_ require
-/// the `switch` feature.
+/// Same as [`switch()`], but for mutable references. The source type is
+/// inferred, so there's no need for [`from()`]. This function requires the
+/// `switch` feature.
 ///
 /// ```rust
 /// # use std::fmt::Debug;
-/// fn specialized_function<T: Debug + 'static>(val: T) -> String {
-///     cismute::switch!(val; T => {
-///         x: i32 => format!("got an i32: {x}"),
-///         x: char => format!("got a char: {x}"),
-///     }).unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// fn specialized_function<T: Debug + 'static>(val: &mut T) -> String {
+///     cismute::switch_mutable(
+///         val,
+///         (
+///             |x: &mut i32| {
+///                 *x += 1;
+///                 format!("got an i32: {x}")
+///             },
+///             |x: &mut char| format!("got a char: {x}"),
+///         ),
+///     )
+///     .unwrap_or_else(|x| format!("got something else: {x:?}"))
 /// }
 ///
-/// assert_eq!(specialized_function(42_i32), "got an i32: 42");
-/// assert_eq!(specialized_function('!'), "got a char: !");
-/// assert_eq!(specialized_function([1, 2]), "got something else: [1, 2]");
+/// assert_eq!(specialized_function(&mut 42_i32), "got an i32: 43");
+/// assert_eq!(specialized_function(&mut '!'), "got a char: !");
+/// assert_eq!(
+///     specialized_function(&mut [1, 2]),
+///     "got something else: [1, 2]"
+/// );
 /// ```
-///
-/// It can also be used with (possibly mutable) references:
+#[cfg_attr(not(feature = "compact"), inline(always))]
+#[cfg(feature = "switch")]
+pub fn switch_mutable<'a, R, T, Args, Tuple>(
+    val: &'a mut T,
+    branches: Tuple,
+) -> Result<R, &'a mut T>
+where
+    Tuple: Branches<R, T, &'a mut T, Args>,
+{
+    branches.dispatch(val)
+}
+
+/// Helper function for [`switch()`].
+#[inline(always)]
+#[cfg(feature = "switch")]
+pub fn from<R, T, P, RefT, Args, Tuple>(_: P, branches: Tuple) -> impl Branches<R, T, RefT, Args>
+where
+    P: Phantom<T>,
+    Tuple: Branches<R, T, RefT, Args>,
+{
+    branches
+}
+
+/// Combines two sets of branches for [`switch()`] into one. Branches of
+/// `first` are tried before branches of `second`. This function requires the
+/// `switch` feature.
+///
+/// This allows e.g. a library to accept additional branches from its users:
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// fn describe<T: Debug + 'static>(val: T) -> String {
+///     describe_with(val, |x: bool| format!("got a bool: {x}"))
+/// }
+///
+/// fn describe_with<T: Debug + 'static, F: FnOnce(bool) -> String>(val: T, extra: F) -> String {
+///     cismute::switch(
+///         val,
+///         cismute::chain(
+///             (
+///                 |x: i32| format!("got an i32: {x}"),
+///                 |x: char| format!("got a char: {x}"),
+///             ),
+///             (extra,),
+///         ),
+///     )
+///     .unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// assert_eq!(describe(42_i32), "got an i32: 42");
+/// assert_eq!(describe(true), "got a bool: true");
+/// assert_eq!(describe([1, 2]), "got something else: [1, 2]");
+/// ```
+#[inline(always)]
+#[cfg(feature = "switch")]
+pub fn chain<R, T, RefT, ArgsFirst, ArgsSecond, First, Second>(
+    first: First,
+    second: Second,
+) -> impl Branches<R, T, RefT, (ArgsFirst, ArgsSecond)>
+where
+    First: Branches<R, T, RefT, ArgsFirst>,
+    Second: Branches<R, T, RefT, ArgsSecond>,
+{
+    branches::Chain(first, second)
+}
+
+/// Applies `f` to the result of any of the matched `branches` for
+/// [`switch()`]. This function requires the `switch` feature.
+///
+/// ```rust
+/// fn size_of_val<T: 'static>(val: &T) -> Option<usize> {
+///     cismute::switch_reference(
+///         val,
+///         cismute::map_output((|x: &String| x.len(), |x: &Vec<u8>| x.len()), |len| len + 1),
+///     )
+///     .ok()
+/// }
+///
+/// assert_eq!(size_of_val(&String::from("abc")), Some(4));
+/// assert_eq!(size_of_val(&vec![1_u8]), Some(2));
+/// assert_eq!(size_of_val(&42), None);
+/// ```
+#[inline(always)]
+#[cfg(feature = "switch")]
+pub fn map_output<R, NewR, T, RefT, Args, Tuple, F>(
+    branches: Tuple,
+    f: F,
+) -> impl Branches<NewR, T, RefT, (R, Args)>
+where
+    Tuple: Branches<R, T, RefT, Args>,
+    F: FnOnce(R) -> NewR,
+{
+    branches::MapOutput(branches, f)
+}
+
+/// Attaches a fallback to `branches` for [`switch()`], which is called with the
+/// original value if none of the branches matched, so [`switch()`] always
+/// returns `Ok(_)` for the result. This function requires the `switch`
+/// feature.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// fn specialized_function<T: Debug + 'static>(val: T) -> Result<String, T> {
+///     cismute::switch(
+///         val,
+///         cismute::or_else((|x: i32| format!("got an i32: {x}"),), |x| {
+///             format!("got something else: {x:?}")
+///         }),
+///     )
+/// }
+///
+/// assert_eq!(specialized_function(42_i32).unwrap(), "got an i32: 42");
+/// assert_eq!(
+///     specialized_function('!').unwrap(),
+///     "got something else: '!'"
+/// );
+/// ```
+#[inline(always)]
+#[cfg(feature = "switch")]
+pub fn or_else<R, T, RefT, Args, Tuple, F>(branches: Tuple, f: F) -> impl Branches<R, T, RefT, Args>
+where
+    Tuple: Branches<R, T, RefT, Args>,
+    F: FnOnce(RefT) -> R,
+{
+    branches::OrElse(branches, f)
+}
+
+/// Returns the index of the first branch of `branches` that would be called by
+/// [`switch()`] for a value of type `RefT`, without calling it. Branches added
+/// with [`chain()`] and [`or_else()`] are counted after the original ones.
+/// This function requires the `switch` feature.
+///
+/// The first argument specifies `RefT`, which is the type of the value, not
+/// the source type. Same as with [`switch()`], the source type has to be
+/// specified with [`from()`] for references.
+///
+/// ```rust
+/// use std::marker::PhantomData as X;
+///
+/// let branches = (
+///     |x: i32| format!("got an i32: {x}"),
+///     |x: char| format!("got a char: {x}"),
+/// );
+/// assert_eq!(cismute::probe(X::<i32>, &branches), Some(0));
+/// assert_eq!(cismute::probe(X::<char>, &branches), Some(1));
+/// assert_eq!(cismute::probe(X::<u8>, &branches), None);
+///
+/// let branches = cismute::chain(branches, (|x: u8| format!("got an u8: {x}"),));
+/// assert_eq!(cismute::probe(X::<u8>, &branches), Some(2));
+///
+/// let branches = cismute::from(
+///     X::<char>,
+///     (|x: &i32| x.to_string(), |x: &char| x.to_string()),
+/// );
+/// assert_eq!(cismute::probe(X::<&char>, &branches), Some(1));
+/// ```
+#[inline(always)]
+#[cfg(feature = "switch")]
+pub fn probe<R, T, P, RefT, Args, Tuple>(_: P, branches: &Tuple) -> Option<usize>
+where
+    P: Phantom<RefT>,
+    Tuple: Branches<R, T, RefT, Args>,
+{
+    branches.probe().ok()
+}
+
+/// Try to match a value with any number of types. This macro _does not_ require
+/// the `switch` feature.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// fn specialized_function<T: Debug + 'static>(val: T) -> String {
+///     cismute::switch!(val; T => {
+///         x: i32 => format!("got an i32: {x}"),
+///         x: char => format!("got a char: {x}"),
+///     }).unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// assert_eq!(specialized_function(42_i32), "got an i32: 42");
+/// assert_eq!(specialized_function('!'), "got a char: !");
+/// assert_eq!(specialized_function([1, 2]), "got something else: [1, 2]");
+/// ```
+///
+/// It can also be used with (possibly mutable) references:
 ///
 /// ```rust
 /// # use std::fmt::Debug;
@@ -319,21 +1316,613 @@ where
 /// assert_eq!(specialized_function(&mut '!'), "got a char: !");
 /// assert_eq!(specialized_function(&mut [1, 2]), "got something else: [1, 2]");
 /// ````
+///
+/// Arm types can be arbitrary type expressions, including generic types with
+/// several arguments, qualified paths and lifetimes:
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// use std::collections::HashMap;
+///
+/// fn specialized_function<T: Debug + 'static>(val: T) -> String {
+///     cismute::switch!(val; T => {
+///         x: HashMap<String, i32> => format!("got a map with {} entries", x.len()),
+///         x: &'static str => format!("got a static str: {x}"),
+///         x: <u8 as std::ops::Add>::Output => format!("got an u8: {x}"),
+///         x: fn(i32, i32) -> i32 => format!("got a function: {}", x(2, 2)),
+///     }).unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// let map = HashMap::from([(String::from("a"), 1), (String::from("b"), 2)]);
+/// assert_eq!(specialized_function(map), "got a map with 2 entries");
+/// assert_eq!(specialized_function(":)"), "got a static str: :)");
+/// assert_eq!(specialized_function(42_u8), "got an u8: 42");
+/// assert_eq!(
+///     specialized_function((|a, b| a + b) as fn(i32, i32) -> i32),
+///     "got a function: 4",
+/// );
+/// assert_eq!(specialized_function([1, 2]), "got something else: [1, 2]");
+/// ```
+///
+/// Arms are tried in order, so an arm for the source type itself (e.g.
+/// `x: T => ...` in the examples above) always matches and makes all the
+/// following arms unreachable. This can't be detected at compile time on
+/// stable Rust, since [`TypeId`]s can't be compared in const context.
+///
+/// Otherwise the order of arms doesn't affect performance: all comparisons
+/// are folded after monomorphization, so an arm for the most common type
+/// doesn't have to go first.
+///
+/// Without optimizations the arms are compared one by one. For large sets of
+/// types, [`JumpTable`] sorts its handlers by [`TypeId`] once and finds the
+/// matching one with a binary search.
+///
+/// Any irrefutable pattern can be used instead of a plain binding:
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// #[derive(Debug)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// fn specialized_function<T: Debug + 'static>(val: T) -> String {
+///     cismute::switch!(val; T => {
+///         (a, b): (i32, i32) => format!("got a pair: {a}, {b}"),
+///         Point { x, y }: Point => format!("got a point: ({x}, {y})"),
+///         [first, ..]: [u8; 4] => format!("got an array starting with {first}"),
+///         _: char => "got a char".to_owned(),
+///     }).unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// assert_eq!(specialized_function((1, 2)), "got a pair: 1, 2");
+/// assert_eq!(specialized_function(Point { x: 3, y: 4 }), "got a point: (3, 4)");
+/// assert_eq!(specialized_function([5_u8; 4]), "got an array starting with 5");
+/// assert_eq!(specialized_function('!'), "got a char");
+/// assert_eq!(specialized_function(42), "got something else: 42");
+/// ```
+///
+/// Arm expressions can use `break`, `continue` and `return`, which work the
+/// same way as in a regular `match`:
+///
+/// ```rust
+/// fn first_positive<T: 'static>(values: &[T]) -> Option<usize> {
+///     for (index, val) in values.iter().enumerate() {
+///         cismute::switch!(val; T => {
+///             x: i32 => if *x <= 0 { continue },
+///             x: u32 => if *x == 0 { continue },
+///         })
+///         .ok()?;
+///         return Some(index);
+///     }
+///     None
+/// }
+///
+/// assert_eq!(first_positive(&[-1_i32, 0, 5]), Some(2));
+/// assert_eq!(first_positive(&[0_u32, 3]), Some(1));
+/// assert_eq!(first_positive(&[0_u32]), None);
+/// assert_eq!(first_positive(&['x']), None);
+/// ```
+///
+/// When switching on an owned value, `ref` and `ref mut` arms inspect it
+/// without consuming: their expression (which must be `()`) is evaluated with
+/// a (mutable) reference, and then matching continues with the next arms, so
+/// the value still reaches them or the fallback.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// fn specialized_function<T: Debug + 'static>(val: T, log: &mut Vec<String>) -> String {
+///     cismute::switch!(val; T => {
+///         ref x: Vec<u8> => log.push(format!("inspecting {} bytes", x.len())),
+///         ref mut x: String => x.push('!'),
+///         x: i32 => format!("got an i32: {x}"),
+///     })
+///     .unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// let mut log = Vec::new();
+/// assert_eq!(
+///     specialized_function(vec![1_u8, 2], &mut log),
+///     "got something else: [1, 2]"
+/// );
+/// assert_eq!(log, ["inspecting 2 bytes"]);
+/// assert_eq!(
+///     specialized_function(String::from("hi"), &mut log),
+///     r#"got something else: "hi!""#
+/// );
+/// assert_eq!(specialized_function(42_i32, &mut log), "got an i32: 42");
+/// ```
+///
+/// Arms can be disabled with `#[cfg(...)]`, the same way as match arms:
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// fn specialized_function<T: Debug + 'static>(val: T) -> String {
+///     cismute::switch!(val; T => {
+///         #[cfg(any())]
+///         x: TypeThatDoesNotExist => unreachable!(),
+///         #[cfg(all())]
+///         x: i32 => format!("got an i32: {x}"),
+///         #[cfg(any())]
+///         x: char => unreachable!(),
+///     }).unwrap_or_else(|x| format!("got something else: {x:?}"))
+/// }
+///
+/// assert_eq!(specialized_function(42_i32), "got an i32: 42");
+/// assert_eq!(specialized_function('!'), "got something else: '!'");
+/// ```
+///
+/// Arms can also be futures, e.g. `async` blocks. Writing `async` before the
+/// arms wraps them into a single [`SwitchFuture`], so the result is
+/// `Result<impl Future, T>` and no boxing is required.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// async fn specialized_function<T: Debug + 'static>(val: T) -> String {
+///     let matched = cismute::switch!(val; T => async {
+///         x: i32 => async move { format!("got an i32: {x}") },
+///         x: char => async move { format!("got a char: {x}") },
+///     });
+///     match matched {
+///         Ok(future) => future.await,
+///         Err(x) => format!("got something else: {x:?}"),
+///     }
+/// }
+/// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+/// #     let mut future = std::pin::pin!(future);
+/// #     let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+/// #     loop {
+/// #         if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+/// #             return output;
+/// #         }
+/// #     }
+/// # }
+///
+/// assert_eq!(block_on(specialized_function(42_i32)), "got an i32: 42");
+/// assert_eq!(block_on(specialized_function('!')), "got a char: !");
+/// assert_eq!(
+///     block_on(specialized_function([1, 2])),
+///     "got something else: [1, 2]"
+/// );
+/// ```
 #[macro_export]
 macro_rules! switch {
-    ($val:expr; $source:ty => { $($name:ident: $type:ty => $expr:expr),+ $(,)? }) => {
-        #[allow(clippy::never_loop)]
+    ($val:expr; $source:ty => { $($arms:tt)+ }) => {
         match $val {
-            val => loop {
+            #[allow(unused_mut)]
+            mut val => $crate::switch!(@arms sync val; $source; [] $($arms)+),
+        }
+    };
+    ($val:expr; $source:ty => async { $($arms:tt)+ }) => {
+        match $val {
+            val => $crate::switch!(@arms async val; $source; [] $($arms)+),
+        }
+    };
+
+    // Arms are expanded into nested `match`es instead of a loop, so `break` and
+    // `continue` in arm expressions refer to the user's loops
+    (@arms sync $val:ident; $source:ty; []) => {
+        ::core::result::Result::Err($val)
+    };
+    (@arms sync $val:ident; $source:ty; [$($pat:tt)+] : $type:ty => $expr:expr $(, $($rest:tt)*)?) => {
+        match $crate::value_with($crate::Pair::<$source, $type>::of(), $val) {
+            ::core::result::Result::Ok($($pat)+) => ::core::result::Result::Ok($expr),
+            ::core::result::Result::Err($val) => {
+                $crate::switch!(@arms sync $val; $source; [] $($($rest)*)?)
+            }
+        }
+    };
+    (@arms sync $val:ident; $source:ty; [] ref $name:ident : $type:ty => $expr:expr $(, $($rest:tt)*)?) => {{
+        if let ::core::result::Result::Ok($name) = $crate::reference::<$source, $type>(&$val) {
+            let () = $expr;
+        }
+        $crate::switch!(@arms sync $val; $source; [] $($($rest)*)?)
+    }};
+    (@arms sync $val:ident; $source:ty; [] ref mut $name:ident : $type:ty => $expr:expr $(, $($rest:tt)*)?) => {{
+        if let ::core::result::Result::Ok($name) = $crate::mutable::<$source, $type>(&mut $val) {
+            let () = $expr;
+        }
+        $crate::switch!(@arms sync $val; $source; [] $($($rest)*)?)
+    }};
+    // Every future but the last one is wrapped into `SwitchFuture::First`, and
+    // the futures of the following arms into `SwitchFuture::Rest`
+    (@arms async $val:ident; $source:ty; []) => {
+        ::core::result::Result::Err::<::core::future::Pending<_>, _>($val)
+    };
+    (@arms async $val:ident; $source:ty; [$($pat:tt)+] : $type:ty => $expr:expr $(,)?) => {
+        match $crate::value_with($crate::Pair::<$source, $type>::of(), $val) {
+            ::core::result::Result::Ok($($pat)+) => ::core::result::Result::Ok($expr),
+            ::core::result::Result::Err($val) => ::core::result::Result::Err($val),
+        }
+    };
+    (@arms async $val:ident; $source:ty; [$($pat:tt)+] : $type:ty => $expr:expr, $($rest:tt)+) => {
+        match $crate::value_with($crate::Pair::<$source, $type>::of(), $val) {
+            ::core::result::Result::Ok($($pat)+) => {
+                ::core::result::Result::Ok($crate::SwitchFuture::First($expr))
+            }
+            ::core::result::Result::Err($val) => {
+                $crate::switch!(@arms async $val; $source; [] $($rest)+)
+                    .map($crate::SwitchFuture::Rest)
+            }
+        }
+    };
+    // Disabled arms are skipped without looking at their type, which may not
+    // exist in this configuration
+    (@arms $mode:ident $val:ident; $source:ty; [] #[cfg($($cfg:tt)*)] $($rest:tt)*) => {{
+        #[cfg($($cfg)*)]
+        let result = $crate::switch!(@arms $mode $val; $source; [] $($rest)*);
+        #[cfg(not($($cfg)*))]
+        let result = $crate::switch!(@skip $mode $val; $source; [] $($rest)*);
+        result
+    }};
+    (@skip $mode:ident $val:ident; $source:ty; [$($pat:tt)*] : $type:ty => $expr:expr $(, $($rest:tt)*)?) => {
+        $crate::switch!(@arms $mode $val; $source; [] $($($rest)*)?)
+    };
+    (@skip $mode:ident $val:ident; $source:ty; [$($pat:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::switch!(@skip $mode $val; $source; [$($pat)* $next] $($rest)*)
+    };
+    // Patterns can't be followed by `:` in `macro_rules!`, so we have to munch
+    // them token by token, reporting arms that end without a type
+    (@$step:ident $mode:ident $val:ident; $source:ty; [$($pat:tt)+]) => {
+        ::core::compile_error!(::core::concat!(
+            "expected `switch!()` arm `pattern: Type => expression`, found `",
+            ::core::stringify!($($pat)+),
+            "`",
+        ))
+    };
+    (@arms $mode:ident $val:ident; $source:ty; [$($pat:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::switch!(@arms $mode $val; $source; [$($pat)* $next] $($rest)*)
+    };
+}
+
+/// Returns from the enclosing function if a variable has the given type.
+/// Otherwise the variable is left untouched, so the generic code can follow.
+/// This macro _does not_ require the `switch` feature.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// fn specialized_function<T: Debug + 'static>(val: T) -> String {
+///     cismute::return_if!(val as i32 => format!("got an i32: {val}"));
+///     cismute::return_if!(val as char => format!("got a char: {val}"));
+///
+///     format!("got something else: {val:?}")
+/// }
+///
+/// assert_eq!(specialized_function(42_i32), "got an i32: 42");
+/// assert_eq!(specialized_function('!'), "got a char: !");
+/// assert_eq!(specialized_function([1, 2]), "got something else: [1, 2]");
+/// ```
+///
+/// References aren't owned values, so the source type has to be specified for
+/// them, similarly to [`switch!()`]:
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// fn specialized_function<T: Debug + 'static>(val: &mut T) -> String {
+///     cismute::return_if!(val: T as i32 => {
+///         *val += 1;
+///         format!("got an i32: {val}")
+///     });
+///
+///     format!("got something else: {val:?}")
+/// }
+///
+/// assert_eq!(specialized_function(&mut 42_i32), "got an i32: 43");
+/// assert_eq!(specialized_function(&mut '!'), "got something else: '!'");
+/// ```
+///
+/// This also works for specializing methods of generic trait impls, which is
+/// the closest stable Rust can get to `default fn`:
+///
+/// ```rust
+/// use std::fmt::{self, Debug, Display};
+///
+/// struct Wrapper<T>(T);
+///
+/// impl<T: Debug + 'static> Display for Wrapper<T> {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         let inner = &self.0;
+///         cismute::return_if!(inner: T as u8 => write!(f, "{inner:#04x}"));
+///         cismute::return_if!(inner: T as String => f.write_str(inner));
+///
+///         write!(f, "{inner:?}")
+///     }
+/// }
+///
+/// assert_eq!(Wrapper(10_u8).to_string(), "0x0a");
+/// assert_eq!(Wrapper(String::from("hi")).to_string(), "hi");
+/// assert_eq!(Wrapper([1, 2]).to_string(), "[1, 2]");
+/// ```
+///
+/// A common use is a fast path for bytes in code generic over the element
+/// type, e.g. a single `memcpy` instead of a loop:
+///
+/// ```rust
+/// fn encode<T: Copy + Into<u32> + 'static>(data: &Vec<T>, out: &mut Vec<u8>) {
+///     cismute::return_if!(data: Vec<T> as Vec<u8> => out.extend_from_slice(data));
+///
+///     for &x in data {
+///         out.extend_from_slice(&x.into().to_le_bytes());
+///     }
+/// }
+///
+/// let mut out = Vec::new();
+/// encode(&vec![1_u8, 2], &mut out);
+/// encode(&vec![3_u16], &mut out);
+/// assert_eq!(out, [1, 2, 3, 0, 0, 0]);
+/// ```
+#[macro_export]
+macro_rules! return_if {
+    ($val:ident as $type:ty => $expr:expr) => {
+        let $val = match $crate::owned::<_, $type>($val) {
+            ::core::result::Result::Ok($val) => return $expr,
+            ::core::result::Result::Err($val) => $val,
+        };
+    };
+    ($val:ident : $source:ty as $type:ty => $expr:expr) => {
+        let $val = match $crate::value_with($crate::Pair::<$source, $type>::of(), $val) {
+            ::core::result::Result::Ok($val) => return $expr,
+            ::core::result::Result::Err($val) => $val,
+        };
+    };
+}
+
+/// Calls `fast` if the value is of one of the listed types, and `generic`
+/// otherwise. Both are called with the value as the only argument, so `fast`
+/// can be a generic function bounded by a trait all listed types implement:
+///
+/// ```rust
+/// fn widen_fast<T: Into<u64>>(val: T) -> Option<u64> {
+///     Some(val.into())
+/// }
+///
+/// fn widen_generic<T>(_val: T) -> Option<u64> {
+///     None
+/// }
+///
+/// fn widen<T: 'static>(val: T) -> Option<u64> {
+///     cismute::maybe_specialized!(val; u8 | u16 | u32 => widen_fast, _ => widen_generic)
+/// }
+///
+/// assert_eq!(widen(42_u16), Some(42));
+/// assert_eq!(widen(42_i16), None);
+/// ```
+///
+/// This is the smallest change that adds a fast path to existing code, since
+/// the original generic function stays as is.
+#[macro_export]
+macro_rules! maybe_specialized {
+    ($val:expr; $($type:ty)|+ => $fast:path, _ => $generic:path $(,)?) => {
+        match $val {
+            val => 'specialized: {
+                $(
+                    let val = match $crate::owned::<_, $type>(val) {
+                        ::core::result::Result::Ok(val) => break 'specialized $fast(val),
+                        ::core::result::Result::Err(val) => val,
+                    };
+                )+
+                $generic(val)
+            }
+        }
+    };
+}
+
+/// Defines an enum with one variant per type, to turn a generic value into a
+/// closed set that can be matched on, e.g. at the boundary of a subsystem
+/// working with only a few types:
+///
+/// ```rust
+/// use cismute::Handle;
+///
+/// cismute::type_enum! {
+///     /// A value of one of the supported column types.
+///     #[derive(Debug, PartialEq)]
+///     pub enum Value {
+///         Int(i64),
+///         Text(String),
+///     }
+/// }
+///
+/// fn store<T: 'static>(val: T) -> Option<Value> {
+///     Value::from_generic(val).ok()
+/// }
+///
+/// assert_eq!(store(42_i64), Some(Value::Int(42)));
+/// assert_eq!(store(42_i32), None);
+/// assert_eq!(Value::Int(42).into_generic::<i64>(), Ok(42));
+///
+/// struct Len;
+///
+/// impl Handle<&i64> for Len {
+///     type Output = usize;
+///
+///     fn handle(&mut self, val: &i64) -> usize {
+///         val.to_string().len()
+///     }
+/// }
+///
+/// impl Handle<&String> for Len {
+///     type Output = usize;
+///
+///     fn handle(&mut self, val: &String) -> usize {
+///         val.len()
+///     }
+/// }
+///
+/// assert_eq!(Value::Int(-1).visit_ref(Len), 2);
+/// assert_eq!(Value::Text(String::from("hi")).visit_ref(Len), 2);
+/// ```
+///
+/// The generated methods are:
+/// * `from_generic<T>(val: T) -> Result<Self, T>`, wrapping the value into the
+///   variant of its type, if there's one;
+/// * `into_generic<T>(self) -> Result<T, Self>`, the other way around;
+/// * `visit<H, R>(self, handler: H) -> R` and `visit_ref` calling the
+///   [`Handle`] implementation for the type of the variant.
+///
+/// Types must be `'static`, and the enum can't have generic parameters.
+#[macro_export]
+macro_rules! type_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident($type:ty)),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant($type)),+
+        }
+
+        impl $name {
+            /// Wraps the value into the variant of its type, if there's one.
+            /// Returns it back otherwise.
+            #[inline(always)]
+            #[allow(dead_code)]
+            $vis fn from_generic<T: 'static>(val: T) -> ::core::result::Result<Self, T> {
                 $(
-                    let val = match $crate::value_with($crate::Pair::<($source, $type)>, val) {
-                        Ok($name) => break Ok($expr),
-                        Err(val) => val,
+                    let val = match $crate::owned::<T, $type>(val) {
+                        ::core::result::Result::Ok(val) => {
+                            return ::core::result::Result::Ok(Self::$variant(val));
+                        }
+                        ::core::result::Result::Err(val) => val,
                     };
                 )+
+                ::core::result::Result::Err(val)
+            }
+
+            /// Returns the wrapped value, if it's of type `T`. Returns itself
+            /// back otherwise.
+            #[inline(always)]
+            #[allow(dead_code)]
+            $vis fn into_generic<T: 'static>(self) -> ::core::result::Result<T, Self> {
+                match self {
+                    $(Self::$variant(val) => $crate::owned::<$type, T>(val).map_err(Self::$variant)),+
+                }
+            }
 
-                break Err(val);
-            },
+            /// Calls the handler for the type of the wrapped value.
+            #[inline(always)]
+            #[allow(dead_code)]
+            $vis fn visit<H, R>(self, mut handler: H) -> R
+            where
+                $(H: $crate::Handle<$type, Output = R>),+
+            {
+                match self {
+                    $(Self::$variant(val) => $crate::Handle::<$type>::handle(&mut handler, val)),+
+                }
+            }
+
+            /// Calls the handler for the type of the wrapped value with a
+            /// reference to it.
+            #[inline(always)]
+            #[allow(dead_code)]
+            $vis fn visit_ref<'a, H, R>(&'a self, mut handler: H) -> R
+            where
+                $(H: $crate::Handle<&'a $type, Output = R>),+
+            {
+                match self {
+                    $(Self::$variant(val) => $crate::Handle::<&'a $type>::handle(&mut handler, val)),+
+                }
+            }
         }
     };
 }
+
+/// Checks if a type is one of the listed types, comparing them like
+/// [`cismute::same`](same()). Comparisons are folded by the optimizer, so this
+/// expression should compile to a constant.
+///
+/// ```rust
+/// fn buffer_size<T: 'static>() -> usize {
+///     if cismute::matches_type!(T, u8 | i8) {
+///         4096
+///     } else {
+///         256
+///     }
+/// }
+///
+/// assert_eq!(buffer_size::<u8>(), 4096);
+/// assert_eq!(buffer_size::<i8>(), 4096);
+/// assert_eq!(buffer_size::<String>(), 256);
+/// ```
+#[macro_export]
+macro_rules! matches_type {
+    ($source:ty, $($type:ty)|+ $(,)?) => {
+        false $(|| $crate::same::<$source, $type>())+
+    };
+}
+
+/// Asserts that two types are the same, for code paths where it's guaranteed
+/// and the mismatch branch should be impossible rather than silently taken.
+///
+/// With the `nightly` feature, the check is done on monomorphization, so
+/// instantiating the surrounding function with different types is a compile
+/// error. On stable, `TypeId`s can't be compared in const context yet, so it
+/// panics at runtime instead. Either way, it's folded into nothing if the types
+/// are the same.
+///
+/// ```rust
+/// trait Storage {
+///     type Item: 'static;
+/// }
+///
+/// struct Bytes;
+///
+/// impl Storage for Bytes {
+///     type Item = u8;
+/// }
+///
+/// fn first_byte<S: Storage>(items: &[S::Item]) -> u8 {
+///     cismute::assert_same_type!(S::Item, u8);
+///     let Ok(bytes) = cismute::reference::<[S::Item], [u8]>(items) else {
+///         unreachable!()
+///     };
+///     bytes[0]
+/// }
+///
+/// assert_eq!(first_byte::<Bytes>(&[1, 2]), 1);
+/// ```
+#[macro_export]
+macro_rules! assert_same_type {
+    ($left:ty, $right:ty $(,)?) => {
+        $crate::__assert_same_type::<$left, $right>()
+    };
+}
+
+#[cfg(feature = "nightly")]
+#[doc(hidden)]
+#[inline(always)]
+pub const fn __assert_same_type<T: ?Sized + 'static, U: ?Sized + 'static>() {
+    const {
+        assert!(
+            same_type::<T, U>(),
+            "cismute: types passed to `assert_same_type!()` are different",
+        );
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+#[doc(hidden)]
+#[inline(always)]
+#[track_caller]
+pub fn __assert_same_type<T: ?Sized + 'static, U: ?Sized + 'static>() {
+    if !same_type::<T, U>() {
+        mismatch::<T, U>();
+    }
+}
+
+/// Builds an array of [`TypeId`](core::any::TypeId)s of the listed types, e.g.
+/// for [`cismute::is_any_of`](is_any_of()). It can be used in const context.
+///
+/// ```rust
+/// let ids = cismute::type_ids![u8, String];
+/// assert!(ids.contains(&std::any::TypeId::of::<String>()));
+/// ```
+#[macro_export]
+macro_rules! type_ids {
+    ($($type:ty),* $(,)?) => {
+        [$(::core::any::TypeId::of::<$type>()),*]
+    };
+}
@@ -0,0 +1,553 @@
+#[cfg(feature = "paranoid")]
+use core::alloc::Layout;
+use core::{any::TypeId, marker::PhantomData, mem::transmute};
+
+use crate::{generic_transmute, same_name};
+
+/// A type that doesn't contain any lifetimes, so a value of some non-`'static`
+/// type can be matched with it by [`cismute::owned_lifetime_free`],
+/// [`cismute::reference_lifetime_free`] and
+/// [`cismute::mutable_lifetime_free`].
+///
+/// [`cismute::owned_lifetime_free`]: owned_lifetime_free()
+/// [`cismute::reference_lifetime_free`]: reference_lifetime_free()
+/// [`cismute::mutable_lifetime_free`]: mutable_lifetime_free()
+///
+/// The source type is compared by its [`TypeId`] with all lifetimes erased.
+/// Matching two non-`'static` types this way isn't sound: `&'a str` and
+/// `&'static str` have the same erased id, but transmuting one to the other
+/// would extend the lifetime. That's why the target type is required to not
+/// have lifetimes at all:
+///
+/// ```rust,compile_fail
+/// fn extend<T>(val: T) -> Option<&'static str> {
+///     cismute::owned_lifetime_free::<T, &'static str>(val).ok()
+/// }
+/// ```
+///
+/// It's implemented for primitive types, `str`, and slices, arrays, tuples,
+/// [`Option`]s and [`Result`]s of such types. With the `alloc` feature, it's
+/// also implemented for `String`, and `Vec`s and `Box`es of such types.
+///
+/// # Safety
+/// `Self` must not have any lifetime parameters and neither can its type
+/// parameters. Notably, it must not be implemented for `&'static T`, because
+/// `&'a T` would then be matched with it for any `'a`.
+pub unsafe trait LifetimeFree: 'static {}
+
+macro_rules! impl_lifetime_free {
+    ($($type:ty),+ $(,)?) => {$(
+        // SAFETY: primitive types don't have lifetimes
+        unsafe impl LifetimeFree for $type {}
+    )+};
+}
+
+impl_lifetime_free!(
+    (),
+    bool,
+    char,
+    str,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+);
+
+// SAFETY: the element type doesn't have lifetimes
+unsafe impl<T: LifetimeFree> LifetimeFree for [T] {}
+// SAFETY: see above
+unsafe impl<T: LifetimeFree, const N: usize> LifetimeFree for [T; N] {}
+// SAFETY: see above
+unsafe impl<T: LifetimeFree> LifetimeFree for Option<T> {}
+// SAFETY: neither type has lifetimes
+unsafe impl<T: LifetimeFree, E: LifetimeFree> LifetimeFree for Result<T, E> {}
+
+macro_rules! impl_lifetime_free_tuples {
+    () => {};
+    ($first:ident $($rest:ident)*) => {
+        impl_lifetime_free_tuples!($($rest)*);
+
+        // SAFETY: none of the element types have lifetimes
+        unsafe impl<$first: LifetimeFree, $($rest: LifetimeFree,)*> LifetimeFree
+            for ($first, $($rest,)*)
+        {
+        }
+    };
+}
+
+impl_lifetime_free_tuples!(T00 T01 T02 T03 T04 T05 T06 T07 T08 T09 T10 T11);
+
+// SAFETY: `String` doesn't have lifetimes
+#[cfg(feature = "alloc")]
+unsafe impl LifetimeFree for alloc::string::String {}
+// SAFETY: the element type doesn't have lifetimes
+#[cfg(feature = "alloc")]
+unsafe impl<T: LifetimeFree> LifetimeFree for alloc::vec::Vec<T> {}
+// SAFETY: the boxed type doesn't have lifetimes
+#[cfg(feature = "alloc")]
+unsafe impl<T: LifetimeFree + ?Sized> LifetimeFree for alloc::boxed::Box<T> {}
+
+/// Declares a struct or an enum and implements [`LifetimeFree`] for it,
+/// checking at compile time that the types of all fields are [`LifetimeFree`],
+/// so the `unsafe impl` doesn't have to be written by hand. Type parameters are
+/// required to be [`LifetimeFree`] too, and lifetime parameters aren't
+/// accepted at all.
+///
+/// ```rust
+/// cismute::lifetime_free! {
+///     #[derive(Debug, PartialEq)]
+///     pub struct Point<T> {
+///         pub x: T,
+///         pub y: T,
+///     }
+///
+///     #[derive(Debug, PartialEq)]
+///     pub enum Shape {
+///         Dot(Point<i32>),
+///         Line { from: Point<i32>, to: Point<i32> },
+///         Empty,
+///     }
+/// }
+///
+/// fn as_shape<T>(val: &T) -> Option<&Shape> {
+///     cismute::reference_lifetime_free::<T, Shape>(val).ok()
+/// }
+///
+/// assert_eq!(as_shape(&Shape::Empty), Some(&Shape::Empty));
+/// assert_eq!(as_shape(&Point { x: 1, y: 2 }), None);
+/// ```
+///
+/// A field with a lifetime, even `'static`, is an error:
+///
+/// ```rust,compile_fail
+/// cismute::lifetime_free! {
+///     struct Name(&'static str);
+/// }
+/// ```
+///
+/// This is a declarative macro rather than a derive, since the crate has no
+/// proc-macro infrastructure, and declaring the type itself lets the macro see
+/// every field.
+#[macro_export]
+macro_rules! lifetime_free {
+    () => {};
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident $(<$($param:ident),+ $(,)?>)? {
+            $($(#[$field_attr:meta])* $field_vis:vis $field:ident: $type:ty),* $(,)?
+        }
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        $vis struct $name $(<$($param),+>)? {
+            $($(#[$field_attr])* $field_vis $field: $type,)*
+        }
+
+        $crate::__lifetime_free_impl!($name $(<$($param),+>)?; $($type),*);
+        $crate::lifetime_free!($($rest)*);
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident $(<$($param:ident),+ $(,)?>)? (
+            $($(#[$field_attr:meta])* $field_vis:vis $type:ty),* $(,)?
+        );
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        $vis struct $name $(<$($param),+>)? (
+            $($(#[$field_attr])* $field_vis $type,)*
+        );
+
+        $crate::__lifetime_free_impl!($name $(<$($param),+>)?; $($type),*);
+        $crate::lifetime_free!($($rest)*);
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident $(<$($param:ident),+ $(,)?>)? {
+            $(
+                $(#[$variant_attr:meta])* $variant:ident
+                $(($($tuple_type:ty),* $(,)?))?
+                $({$($field:ident: $field_type:ty),* $(,)?})?
+            ),* $(,)?
+        }
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        $vis enum $name $(<$($param),+>)? {
+            $(
+                $(#[$variant_attr])* $variant
+                $(($($tuple_type,)*))?
+                $({$($field: $field_type,)*})?,
+            )*
+        }
+
+        $crate::__lifetime_free_impl!(
+            $name $(<$($param),+>)?;
+            $($($($tuple_type,)*)? $($($field_type,)*)?)*
+        );
+        $crate::lifetime_free!($($rest)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __lifetime_free_impl {
+    ($name:ident $(<$($param:ident),+>)?; $($type:ty),* $(,)?) => {
+        const _: () = {
+            fn assert_lifetime_free<T: ?Sized + $crate::LifetimeFree>() {}
+
+            #[allow(dead_code)]
+            fn check_fields$(<$($param: $crate::LifetimeFree),+>)?() {
+                $(assert_lifetime_free::<$type>();)*
+            }
+        };
+
+        // SAFETY: neither the type parameters nor the fields have lifetimes,
+        // as checked above
+        unsafe impl$(<$($param: $crate::LifetimeFree),+>)? $crate::LifetimeFree
+            for $name $(<$($param),+>)?
+        {
+        }
+    };
+}
+
+trait NonStaticAny {
+    fn type_id(&self) -> TypeId
+    where
+        Self: 'static;
+}
+
+impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+    #[inline(always)]
+    fn type_id(&self) -> TypeId
+    where
+        Self: 'static,
+    {
+        TypeId::of::<T>()
+    }
+}
+
+// `TypeId` of `T` with all lifetimes erased, so `&'a str` and `&'static str`
+// have the same id
+#[inline(always)]
+fn non_static_type_id<T: ?Sized>() -> TypeId {
+    let phantom = PhantomData::<T>;
+    // SAFETY: lifetimes don't affect the layout or the vtable, and the only
+    // method just returns a `TypeId`, which doesn't capture anything
+    let phantom =
+        unsafe { transmute::<&dyn NonStaticAny, &(dyn NonStaticAny + 'static)>(&phantom) };
+    phantom.type_id()
+}
+
+// `U` doesn't have lifetimes, so if `T` is the same type modulo lifetimes, it
+// doesn't have them either, and is exactly `U`
+#[inline(always)]
+fn same<T: ?Sized, U: LifetimeFree + ?Sized>() -> bool {
+    let same = non_static_type_id::<T>() == TypeId::of::<U>()
+        && (!cfg!(feature = "strict") || same_name::<T, U>());
+    #[cfg(feature = "paranoid")]
+    if same && !same_name::<T, U>() {
+        crate::paranoid_abort("cismute: types with the same `TypeId` have different names");
+    }
+    same
+}
+
+/// Transmutes an owned value of type `T` to type `U` if they are the same type.
+/// Returns the passed value back if failed. Unlike
+/// [`cismute::owned`](crate::owned()), `T` doesn't have to be `'static`.
+///
+/// ```rust
+/// fn describe<T>(val: T) -> String {
+///     match cismute::owned_lifetime_free::<T, u32>(val) {
+///         Ok(x) => format!("got an u32: {x}"),
+///         Err(_) => String::from("got something else"),
+///     }
+/// }
+///
+/// let borrowed = String::from(":)");
+/// assert_eq!(describe(42_u32), "got an u32: 42");
+/// assert_eq!(describe(borrowed.as_str()), "got something else");
+/// ```
+#[inline(always)]
+pub fn owned_lifetime_free<T, U: LifetimeFree>(val: T) -> Result<U, T> {
+    if same::<T, U>() {
+        // SAFETY: T and U are the same type
+        Ok(unsafe { generic_transmute::<T, U>(val) })
+    } else {
+        Err(val)
+    }
+}
+
+/// Transmutes reference to type `T` to `&U` if they are the same type.
+/// Returns the passed value back if failed. Unlike
+/// [`cismute::reference`](crate::reference()), `T` doesn't have to be
+/// `'static`.
+///
+/// ```rust
+/// struct Parser<'a> {
+///     input: &'a str,
+/// }
+///
+/// fn position<T>(val: &T) -> Option<usize> {
+///     cismute::reference_lifetime_free::<T, usize>(val)
+///         .ok()
+///         .copied()
+/// }
+///
+/// let input = String::from("fn main() {}");
+/// assert_eq!(position(&Parser { input: &input }), None);
+/// assert_eq!(position(&3_usize), Some(3));
+/// ```
+#[inline(always)]
+pub fn reference_lifetime_free<'a, T: ?Sized, U: LifetimeFree + ?Sized>(
+    val: &'a T,
+) -> Result<&'a U, &'a T> {
+    if same::<T, U>() {
+        #[cfg(feature = "paranoid")]
+        let layout = Layout::for_value(val);
+        // SAFETY: T and U are the same type
+        let val = unsafe { generic_transmute::<&'a T, &'a U>(val) };
+        #[cfg(feature = "paranoid")]
+        crate::check_pointee_layouts(Some(layout), Some(Layout::for_value(val)));
+        Ok(val)
+    } else {
+        Err(val)
+    }
+}
+
+/// Transmutes a mutable reference to type `T` to `&mut U` if they are the same
+/// type. Returns the passed value back if failed. Unlike
+/// [`cismute::mutable`](crate::mutable()), `T` doesn't have to be `'static`.
+///
+/// ```rust
+/// fn reset<T>(val: &mut T) {
+///     if let Ok(x) = cismute::mutable_lifetime_free::<T, i32>(val) {
+///         *x = 0;
+///     }
+/// }
+///
+/// let mut x = 42_i32;
+/// reset(&mut x);
+/// assert_eq!(x, 0);
+///
+/// let mut s = "borrowed";
+/// reset(&mut s);
+/// assert_eq!(s, "borrowed");
+/// ```
+#[inline(always)]
+pub fn mutable_lifetime_free<'a, T: ?Sized, U: LifetimeFree + ?Sized>(
+    val: &'a mut T,
+) -> Result<&'a mut U, &'a mut T> {
+    if same::<T, U>() {
+        #[cfg(feature = "paranoid")]
+        let layout = Layout::for_value(&*val);
+        // SAFETY: T and U are the same type
+        let val = unsafe { generic_transmute::<&'a mut T, &'a mut U>(val) };
+        #[cfg(feature = "paranoid")]
+        crate::check_pointee_layouts(Some(layout), Some(Layout::for_value(&*val)));
+        Ok(val)
+    } else {
+        Err(val)
+    }
+}
+
+/// The `'static` form of a type with lifetimes, e.g. `Expr<'static>` for
+/// `Expr<'a>`, allowing [`cismute::reference_transient`] and
+/// [`cismute::owned_transient`] to match a non-`'static` value with it
+/// while keeping the lifetime.
+///
+/// [`cismute::reference_transient`]: reference_transient()
+/// [`cismute::owned_transient`]: owned_transient()
+///
+/// ```rust
+/// struct Expr<'src> {
+///     text: &'src str,
+/// }
+///
+/// // SAFETY: `Expr` only has one lifetime and is covariant in it
+/// unsafe impl cismute::Transient for Expr<'static> {
+///     type Borrowed<'a> = Expr<'a>;
+/// }
+///
+/// fn describe<T>(node: &T) -> &str {
+///     match cismute::reference_transient::<T, Expr<'static>>(node) {
+///         Ok(expr) => expr.text,
+///         Err(_) => "something else",
+///     }
+/// }
+///
+/// let source = String::from("1 + 2");
+/// let expr = Expr { text: &source };
+/// assert_eq!(describe(&expr), "1 + 2");
+/// assert_eq!(describe(&source), "something else");
+/// ```
+///
+/// # Safety
+/// `Self` must be `'static` and `Self::Borrowed<'a>` must be `Self` with every
+/// lifetime replaced by `'a`, including lifetimes in type arguments. `Self`
+/// must be covariant in all of these lifetimes. Type aliases can hide
+/// lifetimes, so the impl should be written for the type itself.
+pub unsafe trait Transient: 'static {
+    /// `Self` with all lifetimes replaced by `'a`.
+    type Borrowed<'a>;
+}
+
+/// Transmutes reference to type `T` to `&U::Borrowed<'a>` if `T` is the same
+/// type as `U` up to lifetimes. Returns the passed value back if failed.
+///
+/// See [`Transient`] for usage example.
+#[inline(always)]
+pub fn reference_transient<'a, T: ?Sized, U: Transient>(
+    val: &'a T,
+) -> Result<&'a U::Borrowed<'a>, &'a T> {
+    if non_static_type_id::<T>() == TypeId::of::<U>() {
+        // SAFETY: T is `U::Borrowed<'b>` for some `'b: 'a`, since the reference
+        // is valid for `'a`, and it's covariant, so it can be shortened to `'a`
+        Ok(unsafe { generic_transmute::<&'a T, &'a U::Borrowed<'a>>(val) })
+    } else {
+        Err(val)
+    }
+}
+
+/// Transmutes an owned value of type `T` to `U::Borrowed<'a>` if `T` is the
+/// same type as `U` up to lifetimes. Returns the passed value back if failed.
+///
+/// There's no mutable version, since lifetimes behind a mutable reference
+/// can't be shortened.
+///
+/// ```rust
+/// #[derive(Debug, PartialEq)]
+/// struct Token<'src>(&'src str);
+///
+/// // SAFETY: `Token` only has one lifetime and is covariant in it
+/// unsafe impl cismute::Transient for Token<'static> {
+///     type Borrowed<'a> = Token<'a>;
+/// }
+///
+/// fn first_token<'a, T: 'a>(val: T) -> Option<Token<'a>> {
+///     cismute::owned_transient::<T, Token<'static>>(val).ok()
+/// }
+///
+/// let source = String::from("let x");
+/// assert_eq!(first_token(Token(&source[..3])), Some(Token("let")));
+/// assert_eq!(first_token(42), None);
+/// ```
+#[inline(always)]
+pub fn owned_transient<'a, T: 'a, U: Transient>(val: T) -> Result<U::Borrowed<'a>, T> {
+    if non_static_type_id::<T>() == TypeId::of::<U>() {
+        // SAFETY: T is `U::Borrowed<'b>` for some `'b: 'a`, since `T: 'a`, and
+        // it's covariant, so it can be shortened to `'a`
+        Ok(unsafe { generic_transmute::<T, U::Borrowed<'a>>(val) })
+    } else {
+        Err(val)
+    }
+}
+
+/// The `'static` form of a type with a single invariant brand lifetime, like
+/// indices and handles created by `generativity`-style APIs, allowing
+/// [`cismute::owned_branded`], [`cismute::reference_branded`] and
+/// [`cismute::mutable_branded`] to match values with it regardless of the
+/// brand.
+///
+/// [`cismute::owned_branded`]: owned_branded()
+/// [`cismute::reference_branded`]: reference_branded()
+/// [`cismute::mutable_branded`]: mutable_branded()
+///
+/// ```rust
+/// use std::marker::PhantomData;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Index<'id> {
+///     index: usize,
+///     brand: PhantomData<fn(&'id ()) -> &'id ()>,
+/// }
+///
+/// // SAFETY: `Index` only has the brand lifetime
+/// unsafe impl cismute::Branded for Index<'static> {
+///     type Brand<'id> = Index<'id>;
+/// }
+///
+/// // every `T` here is created with the brand `'id`
+/// fn as_index<'id, T>(val: T) -> Option<Index<'id>> {
+///     // SAFETY: `T` has the brand `'id` if it's an `Index`
+///     unsafe { cismute::owned_branded::<'id, T, Index<'static>>(val) }.ok()
+/// }
+///
+/// let index = Index {
+///     index: 3,
+///     brand: PhantomData,
+/// };
+/// assert_eq!(as_index(index), Some(index));
+/// assert_eq!(as_index(3_usize), None);
+/// ```
+///
+/// # Safety
+/// `Self` must be `'static`, and `Self::Brand<'id>` must be `Self` with the
+/// brand lifetime replaced by `'id`. `Self` must not have any other
+/// lifetimes, including in type arguments.
+pub unsafe trait Branded: 'static {
+    /// `Self` with the brand `'id`.
+    type Brand<'id>;
+}
+
+/// Transmutes an owned value of type `T` to `U::Brand<'id>` if `T` is the same
+/// type as `U` up to the brand. Returns the passed value back if failed.
+///
+/// See [`Branded`] for usage example.
+///
+/// # Safety
+/// If `T` is `U` with some brand, the brand must be `'id`.
+#[inline(always)]
+pub unsafe fn owned_branded<'id, T, U: Branded>(val: T) -> Result<U::Brand<'id>, T> {
+    if non_static_type_id::<T>() == TypeId::of::<U>() {
+        // SAFETY: T is `U::Brand<'id>`, as guaranteed by the caller
+        Ok(unsafe { generic_transmute::<T, U::Brand<'id>>(val) })
+    } else {
+        Err(val)
+    }
+}
+
+/// Transmutes reference to type `T` to `&U::Brand<'id>` if `T` is the same
+/// type as `U` up to the brand. Returns the passed value back if failed.
+///
+/// # Safety
+/// If `T` is `U` with some brand, the brand must be `'id`.
+#[inline(always)]
+pub unsafe fn reference_branded<'a, 'id, T: ?Sized, U: Branded>(
+    val: &'a T,
+) -> Result<&'a U::Brand<'id>, &'a T> {
+    if non_static_type_id::<T>() == TypeId::of::<U>() {
+        // SAFETY: T is `U::Brand<'id>`, as guaranteed by the caller
+        Ok(unsafe { generic_transmute::<&'a T, &'a U::Brand<'id>>(val) })
+    } else {
+        Err(val)
+    }
+}
+
+/// Transmutes a mutable reference to type `T` to `&mut U::Brand<'id>` if `T`
+/// is the same type as `U` up to the brand. Returns the passed value back if
+/// failed.
+///
+/// # Safety
+/// If `T` is `U` with some brand, the brand must be `'id`.
+#[inline(always)]
+pub unsafe fn mutable_branded<'a, 'id, T: ?Sized, U: Branded>(
+    val: &'a mut T,
+) -> Result<&'a mut U::Brand<'id>, &'a mut T> {
+    if non_static_type_id::<T>() == TypeId::of::<U>() {
+        // SAFETY: T is `U::Brand<'id>`, as guaranteed by the caller
+        Ok(unsafe { generic_transmute::<&'a mut T, &'a mut U::Brand<'id>>(val) })
+    } else {
+        Err(val)
+    }
+}
@@ -0,0 +1,132 @@
+use core::{fmt, marker::PhantomData};
+
+use crate::{Cons, Handle, Nil, SmallAny, TypeSet};
+
+/// A value of one of `Types`, an ad-hoc sum type without declaring an enum.
+/// `Types` is a [`TypeSet`](trait@TypeSet), and the value is stored in a
+/// [`SmallAny<S>`].
+///
+/// ```rust
+/// use cismute::{Handle, OneOf, TypeSet};
+///
+/// type Number = OneOf<TypeSet![i32, f64]>;
+///
+/// struct Describe;
+///
+/// impl Handle<i32> for Describe {
+///     type Output = String;
+///
+///     fn handle(&mut self, x: i32) -> String {
+///         format!("an i32: {x}")
+///     }
+/// }
+///
+/// impl Handle<f64> for Describe {
+///     type Output = String;
+///
+///     fn handle(&mut self, x: f64) -> String {
+///         format!("an f64: {x}")
+///     }
+/// }
+///
+/// let numbers: Vec<Number> = vec![OneOf::new(1_i32).unwrap(), OneOf::new(0.5_f64).unwrap()];
+/// let described: Vec<_> = numbers.into_iter().map(|x| x.visit(Describe)).collect();
+/// assert_eq!(described, ["an i32: 1", "an f64: 0.5"]);
+///
+/// assert!(Number::new('?').is_err());
+/// ```
+pub struct OneOf<Types, S = [usize; 2]> {
+    val: SmallAny<S>,
+    types: PhantomData<Types>,
+}
+
+impl<Types: TypeSet, S> OneOf<Types, S> {
+    /// Wraps the value if it's of one of `Types`. Returns it back otherwise.
+    #[inline(always)]
+    pub fn new<T: 'static>(val: T) -> Result<Self, T> {
+        if Types::contains::<T>() {
+            Ok(Self {
+                val: SmallAny::new(val),
+                types: PhantomData,
+            })
+        } else {
+            Err(val)
+        }
+    }
+
+    /// Returns `true` if the value is of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.val.is::<T>()
+    }
+
+    /// Returns the value with the original type, if it's `T`. Returns itself
+    /// back otherwise.
+    #[inline(always)]
+    pub fn downcast<T: 'static>(self) -> Result<T, Self> {
+        self.val.downcast().map_err(|val| Self {
+            val,
+            types: PhantomData,
+        })
+    }
+
+    /// Returns a reference to the value, if it's of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.val.downcast_ref()
+    }
+
+    /// Returns a mutable reference to the value, if it's of type `T`.
+    #[inline(always)]
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.val.downcast_mut()
+    }
+
+    /// Calls the handler for the type of the value.
+    #[inline(always)]
+    pub fn visit<H, R>(self, mut handler: H) -> R
+    where
+        H: HandleOneOf<Types, R>,
+    {
+        handler.handle_one_of(self.val)
+    }
+}
+
+impl<Types, S> fmt::Debug for OneOf<Types, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OneOf").finish_non_exhaustive()
+    }
+}
+
+/// A handler for values of every type of `Types`, implemented for types
+/// implementing [`Handle`] for each of them. Used by [`OneOf::visit()`].
+pub trait HandleOneOf<Types, R> {
+    /// Calls the handler for the type of the value.
+    ///
+    /// # Panics
+    /// Panics if the value isn't of any of `Types`, which can't happen for
+    /// values of [`OneOf`].
+    fn handle_one_of<S>(&mut self, val: SmallAny<S>) -> R;
+}
+
+impl<H, R> HandleOneOf<Nil, R> for H {
+    #[inline(always)]
+    fn handle_one_of<S>(&mut self, _val: SmallAny<S>) -> R {
+        unreachable!("cismute: the value isn't of any of the types")
+    }
+}
+
+impl<H, R, Head: 'static, Tail> HandleOneOf<Cons<Head, Tail>, R> for H
+where
+    H: Handle<Head, Output = R> + HandleOneOf<Tail, R>,
+{
+    #[inline(always)]
+    fn handle_one_of<S>(&mut self, val: SmallAny<S>) -> R {
+        match val.downcast::<Head>() {
+            Ok(val) => self.handle(val),
+            Err(val) => HandleOneOf::<Tail, R>::handle_one_of(self, val),
+        }
+    }
+}
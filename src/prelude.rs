@@ -0,0 +1,27 @@
+//! Re-exports types, traits and macros for a glob import:
+//!
+//! ```rust
+//! use cismute::prelude::*;
+//!
+//! fn describe<T: 'static>(val: T) -> String {
+//!     switch!(val; T => {
+//!         x: i32 => format!("got an i32: {x}"),
+//!     })
+//!     .unwrap_or_else(|_| String::from("got something else"))
+//! }
+//!
+//! assert_eq!(describe(42_i32), "got an i32: 42");
+//! assert_eq!(describe('!'), "got something else");
+//! ```
+//!
+//! Functions aren't included, since names like `owned` are too generic, so
+//! they are better called as `cismute::owned()`.
+
+pub use crate::{
+    assert_same_type, lifetime_free, matches_type, maybe_specialized, return_if, switch,
+    transparent, type_enum, type_ids, Branded, Cismutable, Handle, HandleOneOf, LifetimeFree,
+    NotSame, OneOf, Pair, SameAs, Specialized, Switch, Transient, Transparent, TypeEq, TypeSet,
+    VisitType,
+};
+#[cfg(feature = "switch")]
+pub use crate::{branches, DynBranches};
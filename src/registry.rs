@@ -0,0 +1,201 @@
+use alloc::vec::Vec;
+
+use crate::table::{
+    call_mutable_with, call_owned_with, call_reference_with, search, Flavor, Handler, Key, Resolved,
+};
+
+/// A set of handlers that can be extended at runtime, e.g. by plugins, unlike
+/// [`switch()`](crate::switch()) tuples or a [`JumpTable`](crate::JumpTable).
+/// Handlers are kept sorted by [`TypeId`](core::any::TypeId), so dispatching is
+/// a binary search. This type requires the `alloc` feature.
+///
+/// ```rust
+/// let mut registry = cismute::Registry::new();
+/// registry
+///     .register(|x: i32| format!("got an i32: {x}"))
+///     .register(|x: char| format!("got a char: {x}"));
+///
+/// assert_eq!(registry.dispatch(42_i32).unwrap(), "got an i32: 42");
+/// assert_eq!(registry.dispatch('!').unwrap(), "got a char: !");
+/// assert_eq!(registry.dispatch("?"), Err("?"));
+/// ```
+///
+/// Owned values, references and mutable references have separate handlers, like
+/// in [`JumpTable`](crate::JumpTable):
+///
+/// ```rust
+/// let mut registry = cismute::Registry::new();
+/// registry
+///     .register_reference(|x: &Vec<u8>| x.len())
+///     .register_mutable(|x: &mut Vec<u8>| {
+///         x.clear();
+///         0
+///     });
+///
+/// let mut bytes = vec![1_u8, 2];
+/// assert_eq!(registry.dispatch_reference(&bytes), Ok(2));
+/// assert_eq!(registry.dispatch_mutable(&mut bytes), Ok(0));
+/// assert!(bytes.is_empty());
+/// assert_eq!(registry.dispatch(bytes), Err(Vec::new()));
+/// ```
+///
+/// [`Handler`]s are created in const context, so a registry can be collected
+/// from a `static` slice of them. Later handlers replace earlier ones for the
+/// same type:
+///
+/// ```rust
+/// use cismute::{Handler, Registry};
+///
+/// static HANDLERS: [Handler<String>; 2] = [
+///     Handler::owned(|x: i32| format!("got an i32: {x}")),
+///     Handler::owned(|x: i32| format!("got an i32 again: {x}")),
+/// ];
+///
+/// let registry: Registry<String> = HANDLERS.iter().collect();
+/// assert_eq!(registry.len(), 1);
+/// assert_eq!(registry.dispatch(42_i32).unwrap(), "got an i32 again: 42");
+/// ```
+///
+/// Distributed registration, with handlers contributed by several crates at
+/// link time, isn't supported: there's no `linkme` or `inventory`
+/// integration.
+pub struct Registry<R> {
+    keys: Vec<Key>,
+    handlers: Vec<Handler<R>>,
+}
+
+impl<R> Registry<R> {
+    /// Creates an empty registry.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Registers a handler for owned values of type `U`, replacing the previous
+    /// one if there was any.
+    pub fn register<U: 'static>(&mut self, handler: fn(U) -> R) -> &mut Self {
+        self.insert(Handler::owned(handler))
+    }
+
+    /// Registers a handler for references to values of type `U`, replacing
+    /// the previous one if there was any.
+    pub fn register_reference<U: 'static>(&mut self, handler: fn(&U) -> R) -> &mut Self {
+        self.insert(Handler::reference(handler))
+    }
+
+    /// Registers a handler for mutable references to values of type `U`,
+    /// replacing the previous one if there was any.
+    pub fn register_mutable<U: 'static>(&mut self, handler: fn(&mut U) -> R) -> &mut Self {
+        self.insert(Handler::mutable(handler))
+    }
+
+    fn insert(&mut self, handler: Handler<R>) -> &mut Self {
+        match search(&self.keys, handler.key) {
+            Ok(position) => self.handlers[position] = handler,
+            Err(position) => {
+                self.keys.insert(position, handler.key);
+                self.handlers.insert(position, handler);
+            }
+        }
+        self
+    }
+
+    /// Returns the number of registered handlers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if no handlers are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Calls the handler for owned `T` if there's one. Returns the passed
+    /// value back otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn dispatch<T: 'static>(&self, val: T) -> Result<R, T> {
+        let handler = self.find(Key::of::<T>(Flavor::Owned));
+        // SAFETY: the handler was found by the key of `T`
+        unsafe { call_owned_with(handler, val) }
+    }
+
+    /// Calls the handler for `&T` if there's one. Returns the passed value back
+    /// otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn dispatch_reference<'a, T: 'static>(&self, val: &'a T) -> Result<R, &'a T> {
+        let handler = self.find(Key::of::<T>(Flavor::Reference));
+        // SAFETY: the handler was found by the key of `&T`
+        unsafe { call_reference_with(handler, val) }
+    }
+
+    /// Calls the handler for `&mut T` if there's one. Returns the passed value
+    /// back otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn dispatch_mutable<'a, T: 'static>(&self, val: &'a mut T) -> Result<R, &'a mut T> {
+        let handler = self.find(Key::of::<T>(Flavor::Mutable));
+        // SAFETY: the handler was found by the key of `&mut T`
+        unsafe { call_mutable_with(handler, val) }
+    }
+
+    /// Looks up the handlers for `T` once, like
+    /// [`JumpTable::resolve()`](crate::JumpTable::resolve()).
+    #[must_use]
+    pub fn resolve<T: 'static>(&self) -> Resolved<R, T> {
+        Resolved::new(|key| self.find(key))
+    }
+
+    fn find(&self, key: Key) -> Option<&Handler<R>> {
+        search(&self.keys, key)
+            .ok()
+            .map(|position| &self.handlers[position])
+    }
+}
+
+impl<R> Default for Registry<R> {
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> Extend<Handler<R>> for Registry<R> {
+    fn extend<I: IntoIterator<Item = Handler<R>>>(&mut self, handlers: I) {
+        for handler in handlers {
+            self.insert(handler);
+        }
+    }
+}
+
+impl<'a, R: 'a> Extend<&'a Handler<R>> for Registry<R> {
+    fn extend<I: IntoIterator<Item = &'a Handler<R>>>(&mut self, handlers: I) {
+        self.extend(handlers.into_iter().copied());
+    }
+}
+
+impl<R> FromIterator<Handler<R>> for Registry<R> {
+    fn from_iter<I: IntoIterator<Item = Handler<R>>>(handlers: I) -> Self {
+        let mut registry = Self::new();
+        registry.extend(handlers);
+        registry
+    }
+}
+
+impl<'a, R: 'a> FromIterator<&'a Handler<R>> for Registry<R> {
+    fn from_iter<I: IntoIterator<Item = &'a Handler<R>>>(handlers: I) -> Self {
+        handlers.into_iter().copied().collect()
+    }
+}
+
+impl<R> Clone for Registry<R> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            handlers: self.handlers.clone(),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{mutable, owned, reference};
+
+/// Method syntax for cismuting `Self` to `U`, implemented for all `'static`
+/// types. It can be used as a bound to spell out which type a generic
+/// parameter may be specialized to, without [`Cismutable`](crate::Cismutable)
+/// and its reference type parameters.
+///
+/// ```rust
+/// use cismute::SameAs;
+///
+/// fn describe<T: SameAs<String> + SameAs<i32>>(val: &T) -> String {
+///     if let Ok(s) = SameAs::<String>::cismute_ref(val) {
+///         format!("got a string: {s}")
+///     } else if let Ok(x) = SameAs::<i32>::cismute_ref(val) {
+///         format!("got an i32: {x}")
+///     } else {
+///         String::from("got something else")
+///     }
+/// }
+///
+/// assert_eq!(describe(&String::from("hi")), "got a string: hi");
+/// assert_eq!(describe(&42_i32), "got an i32: 42");
+/// assert_eq!(describe(&'!'), "got something else");
+/// ```
+///
+/// With one target type, it's just `val.cismute_ref()`.
+pub trait SameAs<U: ?Sized + 'static>: 'static {
+    /// Same as [`cismute::owned`](crate::owned()).
+    #[inline(always)]
+    fn cismute(self) -> Result<U, Self>
+    where
+        Self: Sized,
+        U: Sized,
+    {
+        owned(self)
+    }
+
+    /// Same as [`cismute::reference`](crate::reference()).
+    #[inline(always)]
+    fn cismute_ref(&self) -> Result<&U, &Self> {
+        reference(self)
+    }
+
+    /// Same as [`cismute::mutable`](crate::mutable()).
+    #[inline(always)]
+    fn cismute_mut(&mut self) -> Result<&mut U, &mut Self> {
+        mutable(self)
+    }
+}
+
+impl<T: ?Sized + 'static, U: ?Sized + 'static> SameAs<U> for T {}
@@ -0,0 +1,152 @@
+use core::mem::{self, MaybeUninit};
+
+use crate::{mutable, owned};
+
+/// Writes `val` into `slot` if `T` and `U` are the same type, dropping the old
+/// value. Returns `val` back if failed.
+///
+/// ```rust
+/// fn reset<T: 'static>(slot: &mut T) {
+///     // strings are cleared instead of being left as is
+///     let _ = cismute::set_if_same(slot, String::new());
+/// }
+///
+/// let mut s = String::from("hi");
+/// reset(&mut s);
+/// assert_eq!(s, "");
+///
+/// let mut x = 42;
+/// reset(&mut x);
+/// assert_eq!(x, 42);
+/// ```
+#[inline(always)]
+pub fn set_if_same<T: 'static, U: 'static>(slot: &mut T, val: U) -> Result<(), U> {
+    *slot = owned(val)?;
+    Ok(())
+}
+
+/// Replaces the value in `slot` with `val` if `T` and `U` are the same type,
+/// like [`mem::replace`](core::mem::replace()), and returns the old value.
+/// Returns `val` back if failed.
+///
+/// ```rust
+/// use std::any::Any;
+///
+/// // stores only values of the slot type, e.g. in a slot map
+/// fn store<T: 'static, U: 'static>(slot: &mut T, val: U) -> Option<Box<dyn Any>> {
+///     cismute::replace_if_same(slot, val)
+///         .ok()
+///         .map(|old| Box::new(old) as Box<dyn Any>)
+/// }
+///
+/// let mut name = String::from("Ferris");
+/// let old = store(&mut name, String::from("Corro")).unwrap();
+/// assert_eq!(old.downcast_ref::<String>().unwrap(), "Ferris");
+/// assert_eq!(name, "Corro");
+/// assert!(store(&mut name, 42).is_none());
+/// ```
+#[inline(always)]
+pub fn replace_if_same<T: 'static, U: 'static>(slot: &mut T, val: U) -> Result<T, U> {
+    Ok(mem::replace(slot, owned(val)?))
+}
+
+/// Swaps the values in `a` and `b` if `T` and `U` are the same type, like
+/// [`mem::swap`](core::mem::swap()). Returns whether they were swapped.
+///
+/// ```rust
+/// fn exchange<T: 'static, U: 'static>(a: &mut T, b: &mut U) -> bool {
+///     cismute::swap_if_same(a, b)
+/// }
+///
+/// let (mut a, mut b) = (1, 2);
+/// assert!(exchange(&mut a, &mut b));
+/// assert_eq!((a, b), (2, 1));
+/// assert!(!exchange(&mut a, &mut "two"));
+/// ```
+#[inline(always)]
+pub fn swap_if_same<T: 'static, U: 'static>(a: &mut T, b: &mut U) -> bool {
+    match mutable::<U, T>(b) {
+        Ok(b) => {
+            mem::swap(a, b);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Clones `src` into `dst` with [`Clone::clone_from`] if `T` and `U` are the
+/// same type, reusing the resources of `dst`. Returns whether it was cloned.
+///
+/// ```rust
+/// fn assign<T: 'static>(dst: &mut T, src: &String) -> bool {
+///     cismute::clone_from_if_same(dst, src)
+/// }
+///
+/// let mut s = String::with_capacity(16);
+/// assert!(assign(&mut s, &String::from("hi")));
+/// assert_eq!(s, "hi");
+/// assert!(!assign(&mut 42, &String::from("hi")));
+/// ```
+#[inline(always)]
+pub fn clone_from_if_same<T: 'static, U: Clone + 'static>(dst: &mut T, src: &U) -> bool {
+    match mutable::<T, U>(dst) {
+        Ok(dst) => {
+            dst.clone_from(src);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Copies `src` into `dst` if `T` and `U` are the same type. Returns whether it
+/// was copied. Unlike [`cismute::clone_from_if_same`](clone_from_if_same()),
+/// it doesn't call any user code, so the matched branch is a plain store.
+///
+/// ```rust
+/// fn fill<T: 'static>(items: &mut [T], byte: u8) {
+///     for item in items {
+///         cismute::copy_from_if_same(item, &byte);
+///     }
+/// }
+///
+/// let mut bytes = [0_u8; 4];
+/// fill(&mut bytes, 7);
+/// assert_eq!(bytes, [7; 4]);
+/// ```
+#[inline(always)]
+pub fn copy_from_if_same<T: 'static, U: Copy + 'static>(dst: &mut T, src: &U) -> bool {
+    match mutable::<T, U>(dst) {
+        Ok(dst) => {
+            *dst = *src;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Writes `val` into an uninitialized `slot` if `T` and `U` are the same type,
+/// like [`MaybeUninit::write`](core::mem::MaybeUninit::write()), e.g. for
+/// in-place construction through an out-pointer. Returns `val` back if failed.
+/// The old contents of the slot aren't dropped.
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+///
+/// fn init_name<T: 'static>(out: &mut MaybeUninit<String>, val: T) -> bool {
+///     cismute::uninit_write_if_same(out, val).is_ok()
+/// }
+///
+/// let mut slot = MaybeUninit::uninit();
+/// assert!(!init_name(&mut slot, 42));
+/// assert!(init_name(&mut slot, String::from("Ferris")));
+/// // SAFETY: the slot was initialized above
+/// assert_eq!(unsafe { slot.assume_init() }, "Ferris");
+/// ```
+#[inline(always)]
+pub fn uninit_write_if_same<T: 'static, U: 'static>(
+    slot: &mut MaybeUninit<T>,
+    val: U,
+) -> Result<(), U> {
+    slot.write(owned(val)?);
+    Ok(())
+}
@@ -0,0 +1,235 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::{
+    any::Any,
+    fmt,
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
+    ptr::{self, NonNull},
+};
+
+use crate::{any::TypeKey, AnyMut, AnyRef, AutoTraits};
+
+// Values that fit are stored inline, others are boxed
+const fn fits<T, S>() -> bool {
+    size_of::<T>() <= size_of::<S>() && align_of::<T>() <= align_of::<S>()
+}
+
+// Returns a pointer to the value of type `T` stored in `storage`
+unsafe fn value_ptr<T, S>(storage: *mut MaybeUninit<S>) -> *mut T {
+    if fits::<T, S>() {
+        storage.cast::<T>()
+    } else {
+        // SAFETY: the value doesn't fit, so the storage holds a box
+        storage.cast::<*mut T>().read()
+    }
+}
+
+unsafe fn erased_ptr<T, S>(storage: *mut MaybeUninit<S>) -> *mut () {
+    value_ptr::<T, S>(storage).cast()
+}
+
+unsafe fn drop_value<T, S>(storage: *mut MaybeUninit<S>) {
+    if fits::<T, S>() {
+        storage.cast::<T>().drop_in_place();
+    } else {
+        #[cfg(feature = "alloc")]
+        // SAFETY: the value doesn't fit, so it's a box created by `new()`
+        drop(Box::from_raw(value_ptr::<T, S>(storage)));
+    }
+}
+
+/// An owned type-erased value, like `Box<dyn Any>`, but stored inline if it
+/// fits into `S`, so small values don't need an allocation. Bigger values are
+/// boxed with the `alloc` feature and are a compile error without it.
+///
+/// `S` only defines the size and alignment of the storage, e.g.
+/// `SmallAny<[u64; 4]>` can hold 32 bytes with an alignment of 8. The default
+/// holds two `usize`s.
+///
+/// It's neither [`Send`] nor [`Sync`], unless `A` says so, see [`AutoTraits`].
+///
+/// ```rust
+/// use cismute::SmallAny;
+///
+/// let mut queue: Vec<SmallAny> = vec![SmallAny::new(42_u32), SmallAny::new('!')];
+///
+/// let x = queue.remove(0).downcast::<u32>().unwrap();
+/// assert_eq!(x, 42);
+/// let c = queue.remove(0);
+/// assert!(c.downcast_ref::<u32>().is_none());
+/// assert_eq!(c.downcast::<char>().unwrap(), '!');
+/// ```
+pub struct SmallAny<S = [usize; 2], A: ?Sized = dyn Any> {
+    storage: MaybeUninit<S>,
+    key: TypeKey,
+    get: unsafe fn(*mut MaybeUninit<S>) -> *mut (),
+    drop: unsafe fn(*mut MaybeUninit<S>),
+    // the value implements the auto traits of `A`
+    marker: PhantomData<A>,
+}
+
+impl<S> SmallAny<S> {
+    /// Erases the type of the value.
+    #[inline(always)]
+    pub fn new<T: 'static>(val: T) -> Self {
+        Self::bounded(val)
+    }
+}
+
+impl<S, A: ?Sized> SmallAny<S, A> {
+    /// Erases the type of the value, keeping the auto traits of `A`.
+    ///
+    /// ```rust
+    /// use std::{any::Any, thread};
+    ///
+    /// use cismute::SmallAny;
+    ///
+    /// let val = SmallAny::<[usize; 2], dyn Any + Send>::bounded(42_u32);
+    /// let val = thread::spawn(move || val.downcast::<u32>().unwrap())
+    ///     .join()
+    ///     .unwrap();
+    /// assert_eq!(val, 42);
+    /// ```
+    #[inline(always)]
+    pub fn bounded<T: 'static>(val: T) -> Self
+    where
+        A: AutoTraits<T>,
+    {
+        #[cfg(feature = "alloc")]
+        const {
+            assert!(
+                fits::<T, S>() || fits::<*mut T, S>(),
+                "cismute: `SmallAny` storage can't even hold a pointer",
+            );
+        }
+        #[cfg(not(feature = "alloc"))]
+        const {
+            assert!(
+                fits::<T, S>(),
+                "cismute: value doesn't fit into `SmallAny` storage, and the `alloc` feature is \
+                 disabled",
+            );
+        }
+
+        let mut storage = MaybeUninit::<S>::uninit();
+        if fits::<T, S>() {
+            // SAFETY: the storage is big and aligned enough
+            unsafe { storage.as_mut_ptr().cast::<T>().write(val) };
+        } else {
+            #[cfg(feature = "alloc")]
+            // SAFETY: the storage can hold a pointer, checked above
+            unsafe {
+                storage
+                    .as_mut_ptr()
+                    .cast::<*mut T>()
+                    .write(Box::into_raw(Box::new(val)));
+            };
+        }
+        Self {
+            storage,
+            key: TypeKey::of::<T>(),
+            get: erased_ptr::<T, S>,
+            drop: drop_value::<T, S>,
+            marker: PhantomData,
+        }
+    }
+
+    /// Forgets the auto traits, e.g. to pass it to code taking `SmallAny<S>`.
+    #[inline(always)]
+    #[must_use]
+    pub fn unbounded(self) -> SmallAny<S> {
+        let this = ManuallyDrop::new(self);
+        SmallAny {
+            // SAFETY: `this` is never dropped, so the value is moved out
+            // exactly once
+            storage: unsafe { ptr::from_ref(&this.storage).read() },
+            key: this.key,
+            get: this.get,
+            drop: this.drop,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the value is of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.key.is::<T>()
+    }
+
+    /// Returns the value with the original type, if it's `T`. Returns itself
+    /// back otherwise.
+    #[inline(always)]
+    pub fn downcast<T: 'static>(self) -> Result<T, Self> {
+        if !self.is::<T>() {
+            return Err(self);
+        }
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: the value is `T` and `this` is never dropped, so it's moved
+        // out exactly once
+        unsafe {
+            let ptr = value_ptr::<T, S>(ptr::from_mut(&mut this.storage));
+            if fits::<T, S>() {
+                Ok(ptr.read())
+            } else {
+                #[cfg(feature = "alloc")]
+                return Ok(*Box::from_raw(ptr));
+                #[cfg(not(feature = "alloc"))]
+                unreachable!()
+            }
+        }
+    }
+
+    /// Returns a reference to the value, if it's of type `T`.
+    #[inline(always)]
+    #[must_use]
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        // SAFETY: the value is `T` and the storage is only read
+        self.is::<T>()
+            .then(|| unsafe { &*value_ptr::<T, S>(ptr::from_ref(&self.storage).cast_mut()) })
+    }
+
+    /// Returns a mutable reference to the value, if it's of type `T`.
+    #[inline(always)]
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        // SAFETY: the value is `T` and `self` is borrowed mutably
+        self.is::<T>()
+            .then(|| unsafe { &mut *value_ptr::<T, S>(ptr::from_mut(&mut self.storage)) })
+    }
+
+    /// Returns an erased reference to the value.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_any_ref(&self) -> AnyRef<'_, A> {
+        // SAFETY: `get` knows the stored type, and the value is only read
+        unsafe {
+            let ptr = (self.get)(ptr::from_ref(&self.storage).cast_mut());
+            AnyRef::from_raw(NonNull::new_unchecked(ptr), self.key)
+        }
+    }
+
+    /// Returns an erased mutable reference to the value.
+    #[inline(always)]
+    pub fn as_any_mut(&mut self) -> AnyMut<'_, A> {
+        // SAFETY: `get` knows the stored type, and `self` is borrowed mutably
+        unsafe {
+            let ptr = (self.get)(ptr::from_mut(&mut self.storage));
+            AnyMut::from_raw(NonNull::new_unchecked(ptr), self.key)
+        }
+    }
+}
+
+impl<S, A: ?Sized> Drop for SmallAny<S, A> {
+    fn drop(&mut self) {
+        // SAFETY: `drop` knows the stored type, and the value is never used
+        // again
+        unsafe { (self.drop)(ptr::from_mut(&mut self.storage)) }
+    }
+}
+
+impl<S, A: ?Sized> fmt::Debug for SmallAny<S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmallAny").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,159 @@
+use crate::owned;
+
+/// Result of cismuting `T` to `U`, an alternative to [`Result`] for when the
+/// types being different isn't an error.
+///
+/// It can be created from what the other functions return:
+///
+/// ```rust
+/// use cismute::Specialized;
+///
+/// fn describe<T: ?Sized + 'static>(val: &T) -> &'static str {
+///     match Specialized::from(cismute::reference::<T, str>(val)) {
+///         Specialized::Same(_) => "a string",
+///         Specialized::Other(_) => "something else",
+///     }
+/// }
+///
+/// assert_eq!(describe("hi"), "a string");
+/// assert_eq!(describe(&42), "something else");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[must_use]
+pub enum Specialized<U, T> {
+    /// The types are the same, so the value is transmuted.
+    Same(U),
+    /// The types are different, so the value is returned as is.
+    Other(T),
+}
+
+/// Transmutes an owned value of type `T` to type `U` if they are the same
+/// type, like [`cismute::owned`](owned()), but returns a [`Specialized`].
+///
+/// ```rust
+/// fn len<T: 'static>(val: T) -> Option<usize> {
+///     cismute::specialize::<T, String>(val)
+///         .same()
+///         .map(|s| s.len())
+/// }
+///
+/// assert_eq!(len(String::from("hi")), Some(2));
+/// assert_eq!(len(42), None);
+/// ```
+#[inline(always)]
+pub fn specialize<T: 'static, U: 'static>(val: T) -> Specialized<U, T> {
+    owned(val).into()
+}
+
+impl<U, T> Specialized<U, T> {
+    /// Returns `true` if the types are the same.
+    #[inline(always)]
+    pub const fn is_same(&self) -> bool {
+        matches!(self, Self::Same(_))
+    }
+
+    /// Returns `true` if the types are different.
+    #[inline(always)]
+    pub const fn is_other(&self) -> bool {
+        matches!(self, Self::Other(_))
+    }
+
+    /// Returns the transmuted value, if the types are the same.
+    #[inline(always)]
+    pub fn same(self) -> Option<U> {
+        match self {
+            Self::Same(val) => Some(val),
+            Self::Other(_) => None,
+        }
+    }
+
+    /// Returns the original value, if the types are different.
+    #[inline(always)]
+    pub fn other(self) -> Option<T> {
+        match self {
+            Self::Same(_) => None,
+            Self::Other(val) => Some(val),
+        }
+    }
+
+    /// Maps the transmuted value, leaving the original one untouched.
+    #[inline(always)]
+    pub fn map_same<V>(self, f: impl FnOnce(U) -> V) -> Specialized<V, T> {
+        match self {
+            Self::Same(val) => Specialized::Same(f(val)),
+            Self::Other(val) => Specialized::Other(val),
+        }
+    }
+
+    /// Maps the original value, leaving the transmuted one untouched.
+    #[inline(always)]
+    pub fn map_other<V>(self, f: impl FnOnce(T) -> V) -> Specialized<U, V> {
+        match self {
+            Self::Same(val) => Specialized::Same(val),
+            Self::Other(val) => Specialized::Other(f(val)),
+        }
+    }
+
+    /// Returns the transmuted value, or converts the original one with `f`.
+    #[inline(always)]
+    pub fn unwrap_or_else(self, f: impl FnOnce(T) -> U) -> U {
+        match self {
+            Self::Same(val) => val,
+            Self::Other(val) => f(val),
+        }
+    }
+
+    /// Returns the transmuted value.
+    ///
+    /// # Panics
+    /// Panics if the types are different.
+    #[allow(clippy::panic)]
+    #[inline(always)]
+    #[track_caller]
+    pub fn unwrap_same(self) -> U {
+        match self {
+            Self::Same(val) => val,
+            Self::Other(_) => panic!("called `Specialized::unwrap_same()` on an `Other` value"),
+        }
+    }
+
+    /// Returns the original value.
+    ///
+    /// # Panics
+    /// Panics if the types are the same.
+    #[allow(clippy::panic)]
+    #[inline(always)]
+    #[track_caller]
+    pub fn unwrap_other(self) -> T {
+        match self {
+            Self::Same(_) => panic!("called `Specialized::unwrap_other()` on a `Same` value"),
+            Self::Other(val) => val,
+        }
+    }
+
+    /// Converts to the [`Result`] the other functions return.
+    #[inline(always)]
+    pub fn into_result(self) -> Result<U, T> {
+        match self {
+            Self::Same(val) => Ok(val),
+            Self::Other(val) => Err(val),
+        }
+    }
+}
+
+impl<U, T> From<Result<U, T>> for Specialized<U, T> {
+    #[inline(always)]
+    fn from(result: Result<U, T>) -> Self {
+        match result {
+            Ok(val) => Self::Same(val),
+            Err(val) => Self::Other(val),
+        }
+    }
+}
+
+impl<T, E> From<Specialized<T, E>> for Result<T, E> {
+    #[inline(always)]
+    fn from(specialized: Specialized<T, E>) -> Self {
+        specialized.into_result()
+    }
+}
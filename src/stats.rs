@@ -0,0 +1,198 @@
+//! Counts how often types matched at each call site, to check whether
+//! specialized paths actually run before investing in more of them. This
+//! module requires the `stats` feature.
+//!
+//! Every call of [`cismute::value`](crate::value()) and the functions built on
+//! it is recorded, attributed to the first caller outside of cismute for
+//! [`owned`](crate::owned()), [`reference`](crate::reference()),
+//! [`mutable`](crate::mutable()) and [`value_with`](crate::value_with()), and
+//! to the call inside cismute otherwise (e.g. the [`Switch`](crate::Switch)
+//! builder).
+//!
+//! **Nothing is recorded with the `nightly` feature**, since `value()` and the
+//! functions built on it are `const fn`s there, and `const fn`s can't update
+//! counters. [`snapshot()`] then returns no call sites.
+//!
+//! ```rust
+//! fn is_byte<T: 'static>(val: T) -> bool {
+//!     cismute::owned::<T, u8>(val).is_ok()
+//! }
+//!
+//! is_byte(1_u8);
+//! is_byte('!');
+//! is_byte('?');
+//!
+//! # #[cfg(not(feature = "nightly"))] {
+//! let sites: Vec<_> = cismute::stats::snapshot().collect();
+//! assert_eq!(sites.len(), 1);
+//! // e.g. `src/main.rs:2:5: 1 hit, 2 misses`
+//! println!("{}", sites[0]);
+//! assert_eq!((sites[0].hits(), sites[0].misses()), (1, 2));
+//! # }
+//! ```
+//!
+//! Counters are atomic, in a fixed table of 256 call sites; calls from other
+//! sites are counted in [`dropped()`].
+
+// nothing is recorded in `const fn`s
+#![cfg_attr(feature = "nightly", allow(dead_code))]
+
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    panic::Location,
+    ptr,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+const SLOTS: usize = 256;
+
+struct Slot {
+    location: AtomicPtr<Location<'static>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            location: AtomicPtr::new(ptr::null_mut()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+}
+
+static TABLE: [Slot; SLOTS] = [const { Slot::new() }; SLOTS];
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+// FNV-1a, since there's no hasher in `core`
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn start(location: &Location<'_>) -> usize {
+    let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+    location.hash(&mut hasher);
+    hasher.finish() as usize % SLOTS
+}
+
+// Locations of the same call site aren't always at the same address, so they
+// are compared by value
+fn slot(location: &'static Location<'static>) -> Option<&'static Slot> {
+    let start = start(location);
+    for i in 0..SLOTS {
+        let slot = &TABLE[(start + i) % SLOTS];
+        let mut current = slot.location.load(Ordering::Acquire);
+        if current.is_null() {
+            current = match slot.location.compare_exchange(
+                ptr::null_mut(),
+                ptr::from_ref(location).cast_mut(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(slot),
+                Err(winner) => winner,
+            };
+        }
+        // SAFETY: only `&'static Location`s are stored
+        if unsafe { *current == *location } {
+            return Some(slot);
+        }
+    }
+    None
+}
+
+#[inline(never)]
+pub(crate) fn record(location: &'static Location<'static>, hit: bool) {
+    match slot(location) {
+        Some(slot) if hit => slot.hits.fetch_add(1, Ordering::Relaxed),
+        Some(slot) => slot.misses.fetch_add(1, Ordering::Relaxed),
+        None => DROPPED.fetch_add(1, Ordering::Relaxed),
+    };
+}
+
+/// Counters of one call site.
+#[derive(Clone, Copy, Debug)]
+pub struct CallSite {
+    location: &'static Location<'static>,
+    hits: usize,
+    misses: usize,
+}
+
+impl CallSite {
+    /// Returns the location of the call.
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns how many times the types matched.
+    #[must_use]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Returns how many times the types were different.
+    #[must_use]
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+impl fmt::Display for CallSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = |n: usize, suffix| if n == 1 { "" } else { suffix };
+        write!(
+            f,
+            "{}: {} hit{}, {} miss{}",
+            self.location,
+            self.hits,
+            suffix(self.hits, "s"),
+            self.misses,
+            suffix(self.misses, "es"),
+        )
+    }
+}
+
+/// Returns the counters of all recorded call sites, in no particular order.
+/// Calls made concurrently may or may not be included.
+pub fn snapshot() -> impl Iterator<Item = CallSite> {
+    TABLE.iter().filter_map(|slot| {
+        let location = slot.location.load(Ordering::Acquire);
+        // SAFETY: only `&'static Location`s are stored
+        let location = unsafe { location.as_ref() }?;
+        Some(CallSite {
+            location,
+            hits: slot.hits.load(Ordering::Relaxed),
+            misses: slot.misses.load(Ordering::Relaxed),
+        })
+    })
+}
+
+/// Returns the number of calls that weren't recorded, because the table of
+/// call sites was full.
+#[must_use]
+pub fn dropped() -> usize {
+    DROPPED.load(Ordering::Relaxed)
+}
+
+/// Resets all counters to zero. Call sites stay in the table.
+pub fn reset() {
+    for slot in &TABLE {
+        slot.hits.store(0, Ordering::Relaxed);
+        slot.misses.store(0, Ordering::Relaxed);
+    }
+    DROPPED.store(0, Ordering::Relaxed);
+}
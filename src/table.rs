@@ -0,0 +1,348 @@
+use core::{
+    any::TypeId,
+    marker::PhantomData,
+    mem::{transmute, ManuallyDrop},
+    ptr,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Flavor {
+    Owned,
+    Reference,
+    Mutable,
+}
+
+// Identity of a handler. Handlers are sorted by id and flavor, names are only
+// compared for equal ids, like in `same_type()`. `type_name()` isn't const, so
+// it's called on lookup
+#[derive(Clone, Copy)]
+pub(crate) struct Key {
+    id: TypeId,
+    flavor: Flavor,
+    #[cfg(any(feature = "strict", feature = "paranoid"))]
+    name: fn() -> &'static str,
+}
+
+impl Key {
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub(crate) const fn of<U: 'static>(flavor: Flavor) -> Self {
+        Self {
+            id: TypeId::of::<U>(),
+            flavor,
+            #[cfg(any(feature = "strict", feature = "paranoid"))]
+            name: core::any::type_name::<U>,
+        }
+    }
+
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn order(self) -> (TypeId, Flavor) {
+        (self.id, self.flavor)
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        let same = self.order() == other.order();
+        #[cfg(any(feature = "strict", feature = "paranoid"))]
+        let same_name = || (self.name)() == (other.name)();
+        #[cfg(feature = "paranoid")]
+        if same && !same_name() {
+            crate::paranoid_abort("cismute: types with the same `TypeId` have different names");
+        }
+        #[cfg(feature = "strict")]
+        let same = same && same_name();
+        same
+    }
+}
+
+// `erased` is a type-erased function pointer, `call` knows its real type
+type Call<R> = unsafe fn(fn(), *mut ()) -> R;
+
+unsafe fn call_owned<U: 'static, R>(handler: fn(), val: *mut ()) -> R {
+    // SAFETY: `handler` was created from `fn(U) -> R` and `val` points to `U`
+    transmute::<fn(), fn(U) -> R>(handler)(val.cast::<U>().read())
+}
+
+unsafe fn call_reference<U: 'static, R>(handler: fn(), val: *mut ()) -> R {
+    // SAFETY: `handler` was created from `fn(&U) -> R` and `val` points to `U`
+    transmute::<fn(), fn(&U) -> R>(handler)(&*val.cast::<U>())
+}
+
+unsafe fn call_mutable<U: 'static, R>(handler: fn(), val: *mut ()) -> R {
+    // SAFETY: `handler` was created from `fn(&mut U) -> R` and `val` points to a
+    // uniquely borrowed `U`
+    transmute::<fn(), fn(&mut U) -> R>(handler)(&mut *val.cast::<U>())
+}
+
+/// A single type-erased handler for [`JumpTable`].
+///
+/// Handlers can be created in const context, so they can be stored in
+/// `static`s. This needs [`TypeId::of()`] to be a `const fn`, which is why the
+/// crate requires Rust 1.91.
+pub struct Handler<R> {
+    pub(crate) key: Key,
+    erased: fn(),
+    call: Call<R>,
+}
+
+impl<R> Clone for Handler<R> {
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for Handler<R> {}
+
+impl<R> Handler<R> {
+    /// Creates a handler for owned values of type `U`.
+    #[must_use]
+    pub const fn owned<U: 'static>(handler: fn(U) -> R) -> Self {
+        Self {
+            key: Key::of::<U>(Flavor::Owned),
+            // SAFETY: all function pointers have the same layout, it's transmuted
+            // back to the original type before calling
+            erased: unsafe { transmute::<fn(U) -> R, fn()>(handler) },
+            call: call_owned::<U, R>,
+        }
+    }
+
+    /// Creates a handler for references to values of type `U`.
+    #[must_use]
+    pub const fn reference<U: 'static>(handler: fn(&U) -> R) -> Self {
+        Self {
+            key: Key::of::<U>(Flavor::Reference),
+            // SAFETY: see `Handler::owned()`
+            erased: unsafe { transmute::<fn(&U) -> R, fn()>(handler) },
+            call: call_reference::<U, R>,
+        }
+    }
+
+    /// Creates a handler for mutable references to values of type `U`.
+    #[must_use]
+    pub const fn mutable<U: 'static>(handler: fn(&mut U) -> R) -> Self {
+        Self {
+            key: Key::of::<U>(Flavor::Mutable),
+            // SAFETY: see `Handler::owned()`
+            erased: unsafe { transmute::<fn(&mut U) -> R, fn()>(handler) },
+            call: call_mutable::<U, R>,
+        }
+    }
+}
+
+/// A reusable set of [`Handler`]s, sorted by [`TypeId`] once on creation.
+/// Dispatching is a binary search, so it takes `O(log n)` comparisons of
+/// [`TypeId`]s for `n` handlers. The search is done by a non-generic function
+/// shared by all tables, only looking up the key of the type and calling the
+/// handler is generic. With the `strict` feature, types with the same
+/// [`TypeId`] but different names are different, even with `nightly`, where
+/// [`cismute::value`](crate::value()) only compares [`TypeId`]s.
+///
+/// If several handlers have the same type and ownership, the first one is
+/// used.
+///
+/// ```rust
+/// use cismute::{Handler, JumpTable};
+///
+/// static HANDLERS: [Handler<String>; 3] = [
+///     Handler::owned(|x: i32| format!("got an i32: {x}")),
+///     Handler::owned(|x: char| format!("got a char: {x}")),
+///     Handler::reference(|x: &String| format!("got a string: {x}")),
+/// ];
+///
+/// let table = JumpTable::new(HANDLERS);
+/// assert_eq!(table.owned(42_i32).unwrap(), "got an i32: 42");
+/// assert_eq!(table.owned('!').unwrap(), "got a char: !");
+/// assert_eq!(
+///     table.reference(&String::from(":)")).unwrap(),
+///     "got a string: :)"
+/// );
+/// // ownership has to match too
+/// assert_eq!(table.owned(String::from(":(")), Err(String::from(":(")));
+/// assert_eq!(table.owned([1, 2]), Err([1, 2]));
+/// ```
+pub struct JumpTable<R, const N: usize> {
+    keys: [Key; N],
+    handlers: [Handler<R>; N],
+}
+
+impl<R, const N: usize> JumpTable<R, N> {
+    /// Creates a new table from an array of handlers.
+    #[must_use]
+    pub fn new(handlers: [Handler<R>; N]) -> Self {
+        let mut order: [usize; N] = core::array::from_fn(|index| index);
+        order.sort_unstable_by_key(|&index| (handlers[index].key.order(), index));
+        Self {
+            keys: order.map(|index| handlers[index].key),
+            handlers: order.map(|index| handlers[index]),
+        }
+    }
+
+    /// Looks up the handlers for `T` once, so they can be called repeatedly,
+    /// e.g. in a hot loop, without searching the table every time.
+    ///
+    /// ```rust
+    /// use cismute::{Handler, JumpTable};
+    ///
+    /// fn total<T: 'static>(table: &JumpTable<u64, 2>, items: &[T]) -> u64 {
+    ///     let handlers = table.resolve::<T>();
+    ///     items
+    ///         .iter()
+    ///         .filter_map(|x| handlers.reference(x).ok())
+    ///         .sum()
+    /// }
+    ///
+    /// let table = JumpTable::new([
+    ///     Handler::reference(|x: &u8| u64::from(*x)),
+    ///     Handler::reference(|x: &String| x.len() as u64),
+    /// ]);
+    /// assert_eq!(total(&table, &[1_u8, 2]), 3);
+    /// assert_eq!(total(&table, &[String::from("hi")]), 2);
+    /// assert_eq!(total(&table, &['!']), 0);
+    /// ```
+    #[must_use]
+    pub fn resolve<T: 'static>(&self) -> Resolved<R, T> {
+        Resolved::new(|key| self.find(key))
+    }
+
+    fn find(&self, key: Key) -> Option<&Handler<R>> {
+        search(&self.keys, key)
+            .ok()
+            .map(|position| &self.handlers[position])
+    }
+
+    /// Calls the handler for owned `T` if there's one. Returns the passed
+    /// value back otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn owned<T: 'static>(&self, val: T) -> Result<R, T> {
+        let handler = self.find(Key::of::<T>(Flavor::Owned));
+        // SAFETY: the handler was found by the key of `T`
+        unsafe { call_owned_with(handler, val) }
+    }
+
+    /// Calls the handler for `&T` if there's one. Returns the passed value back
+    /// otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn reference<'a, T: 'static>(&self, val: &'a T) -> Result<R, &'a T> {
+        let handler = self.find(Key::of::<T>(Flavor::Reference));
+        // SAFETY: the handler was found by the key of `&T`
+        unsafe { call_reference_with(handler, val) }
+    }
+
+    /// Calls the handler for `&mut T` if there's one. Returns the passed value
+    /// back otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn mutable<'a, T: 'static>(&self, val: &'a mut T) -> Result<R, &'a mut T> {
+        let handler = self.find(Key::of::<T>(Flavor::Mutable));
+        // SAFETY: the handler was found by the key of `&mut T`
+        unsafe { call_mutable_with(handler, val) }
+    }
+}
+
+/// Handlers for `T` looked up in advance with
+/// [`JumpTable::resolve()`](JumpTable::resolve()) or `Registry::resolve()`.
+/// Holds copies of the handlers, so it doesn't borrow the table.
+pub struct Resolved<R, T: 'static> {
+    owned: Option<Handler<R>>,
+    reference: Option<Handler<R>>,
+    mutable: Option<Handler<R>>,
+    marker: PhantomData<fn(T)>,
+}
+
+impl<R, T: 'static> Clone for Resolved<R, T> {
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R, T: 'static> Copy for Resolved<R, T> {}
+
+impl<R, T: 'static> Resolved<R, T> {
+    pub(crate) fn new<'a>(find: impl Fn(Key) -> Option<&'a Handler<R>>) -> Self
+    where
+        R: 'a,
+    {
+        Self {
+            owned: find(Key::of::<T>(Flavor::Owned)).copied(),
+            reference: find(Key::of::<T>(Flavor::Reference)).copied(),
+            mutable: find(Key::of::<T>(Flavor::Mutable)).copied(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Calls the handler for owned `T` if there's one. Returns the passed
+    /// value back otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn owned(&self, val: T) -> Result<R, T> {
+        // SAFETY: the handler was found by the key of `T`
+        unsafe { call_owned_with(self.owned.as_ref(), val) }
+    }
+
+    /// Calls the handler for `&T` if there's one. Returns the passed value back
+    /// otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn reference<'a>(&self, val: &'a T) -> Result<R, &'a T> {
+        // SAFETY: the handler was found by the key of `&T`
+        unsafe { call_reference_with(self.reference.as_ref(), val) }
+    }
+
+    /// Calls the handler for `&mut T` if there's one. Returns the passed value
+    /// back otherwise.
+    #[cfg_attr(not(feature = "compact"), inline(always))]
+    pub fn mutable<'a>(&self, val: &'a mut T) -> Result<R, &'a mut T> {
+        // SAFETY: the handler was found by the key of `&mut T`
+        unsafe { call_mutable_with(self.mutable.as_ref(), val) }
+    }
+}
+
+// Finds the first matching key in sorted keys, or where it would be inserted.
+// It isn't generic, so it's compiled once for all tables and registries
+pub(crate) fn search(keys: &[Key], key: Key) -> Result<usize, usize> {
+    let start = keys.partition_point(|other| other.order() < key.order());
+    keys[start..]
+        .iter()
+        .take_while(|other| other.order() == key.order())
+        .position(|&other| other == key)
+        .map(|offset| start + offset)
+        .ok_or(start)
+}
+
+// `handler` must have the key of `T`, `&T` or `&mut T` respectively
+
+#[cfg_attr(not(feature = "compact"), inline(always))]
+pub(crate) unsafe fn call_owned_with<R, T: 'static>(
+    handler: Option<&Handler<R>>,
+    val: T,
+) -> Result<R, T> {
+    let Some(handler) = handler else {
+        return Err(val);
+    };
+    let mut val = ManuallyDrop::new(val);
+    // SAFETY: the handler takes `T`, which is moved out of `val` exactly once
+    Ok(unsafe { (handler.call)(handler.erased, ptr::from_mut::<T>(&mut val).cast()) })
+}
+
+#[cfg_attr(not(feature = "compact"), inline(always))]
+pub(crate) unsafe fn call_reference_with<'a, R, T: 'static>(
+    handler: Option<&Handler<R>>,
+    val: &'a T,
+) -> Result<R, &'a T> {
+    let Some(handler) = handler else {
+        return Err(val);
+    };
+    // SAFETY: the handler takes `&T` and only reads through the pointer
+    Ok(unsafe { (handler.call)(handler.erased, ptr::from_ref(val).cast_mut().cast()) })
+}
+
+#[cfg_attr(not(feature = "compact"), inline(always))]
+pub(crate) unsafe fn call_mutable_with<'a, R, T: 'static>(
+    handler: Option<&Handler<R>>,
+    val: &'a mut T,
+) -> Result<R, &'a mut T> {
+    let Some(handler) = handler else {
+        return Err(val);
+    };
+    // SAFETY: the handler takes `&mut T`
+    Ok(unsafe { (handler.call)(handler.erased, ptr::from_mut(val).cast()) })
+}
@@ -0,0 +1,144 @@
+use core::mem::{align_of, size_of};
+
+use crate::{generic_transmute, same_type, seal::Retype};
+
+/// A `#[repr(transparent)]` wrapper that can be cismuted from and to its inner
+/// type with [`cismute::wrap`](wrap()) and [`cismute::unwrap`](unwrap()).
+///
+/// Use [`transparent!()`](crate::transparent!) to implement it safely.
+/// Either way, any crate can then create the wrapper from its inner value,
+/// regardless of field privacy.
+///
+/// # Safety
+/// `Self` must be `#[repr(transparent)]` with a single field of type
+/// `Self::Inner`, not counting [`PhantomData`](core::marker::PhantomData)s,
+/// and any valid `Self::Inner` must be a valid `Self`.
+pub unsafe trait Transparent: 'static {
+    /// The wrapped type.
+    type Inner: 'static;
+}
+
+// Checks after monomorphization, so a wrong manual impl is caught when used
+#[inline(always)]
+fn assert_layout<W: Transparent>() {
+    const {
+        assert!(size_of::<W>() == size_of::<W::Inner>());
+        assert!(align_of::<W>() == align_of::<W::Inner>());
+    }
+}
+
+/// Cismutes `T` or a (possibly mutable) reference to `T` to the transparent
+/// wrapper `W` with the same ownership, if `T` is the same type as the wrapped
+/// type. Returns the passed value back if failed.
+///
+/// ```rust
+/// cismute::transparent! {
+///     #[derive(Debug, PartialEq)]
+///     pub struct Meters(pub f64);
+/// }
+///
+/// fn to_meters<T: 'static>(val: &T) -> Option<&Meters> {
+///     cismute::wrap::<T, Meters, _>(val).ok()
+/// }
+///
+/// assert_eq!(to_meters(&1.5_f64), Some(&Meters(1.5)));
+/// assert_eq!(to_meters(&1.5_f32), None);
+/// ```
+#[inline(always)]
+pub fn wrap<'a, T, W, RefT>(val: RefT) -> Result<RefT::Output, RefT>
+where
+    T: 'static,
+    W: Transparent,
+    RefT: Retype<'a, T, W>,
+{
+    assert_layout::<W>();
+    if same_type::<T, W::Inner>() {
+        // SAFETY: T is the wrapped type and W is `#[repr(transparent)]`. `Retype`
+        // is sealed, so `RefT` is `T`, `&T` or `&mut T`.
+        Ok(unsafe { generic_transmute::<RefT, RefT::Output>(val) })
+    } else {
+        Err(val)
+    }
+}
+
+/// Cismutes the transparent wrapper `W` or a (possibly mutable) reference to
+/// `W` to `U` with the same ownership, if `U` is the same type as the wrapped
+/// type. Returns the passed value back if failed.
+///
+/// ```rust
+/// cismute::transparent! {
+///     pub struct Wrapper<T>(T);
+/// }
+///
+/// fn inner_len<W: cismute::Transparent>(mut val: W) -> Option<usize> {
+///     cismute::unwrap::<W, String, _>(&mut val).ok().map(|s| {
+///         s.push('!');
+///         s.len()
+///     })
+/// }
+///
+/// assert_eq!(inner_len(Wrapper(String::from("hi"))), Some(3));
+/// assert_eq!(inner_len(Wrapper(42)), None);
+/// ```
+#[inline(always)]
+pub fn unwrap<'a, W, U, RefW>(val: RefW) -> Result<RefW::Output, RefW>
+where
+    W: Transparent,
+    U: 'static,
+    RefW: Retype<'a, W, U>,
+{
+    assert_layout::<W>();
+    if same_type::<W::Inner, U>() {
+        // SAFETY: U is the wrapped type and W is `#[repr(transparent)]`. `Retype`
+        // is sealed, so `RefW` is `W`, `&W` or `&mut W`.
+        Ok(unsafe { generic_transmute::<RefW, RefW::Output>(val) })
+    } else {
+        Err(val)
+    }
+}
+
+/// Declares a `#[repr(transparent)]` newtype and implements [`Transparent`]
+/// for it. Type parameters must be `'static` and can't have bounds. Besides
+/// the wrapped value, the struct can only have a [`PhantomData`] field.
+///
+/// **The wrapper becomes publicly constructible from its inner value, even if
+/// the field is private.** Any crate can create it from any value of the inner
+/// type with [`cismute::wrap`](wrap()), and get the inner value or a mutable
+/// reference to it with [`cismute::unwrap`](unwrap()). Only use it for
+/// wrappers without invariants, never for newtypes that validate their
+/// contents.
+///
+/// [`PhantomData`]: core::marker::PhantomData
+///
+/// ```rust
+/// cismute::transparent! {
+///     /// A label, any string is valid.
+///     #[derive(Debug, Clone)]
+///     pub struct Label(pub String);
+///
+///     pub(crate) struct Tagged<T, Tag>(pub T, PhantomData<Tag>);
+/// }
+/// ```
+#[macro_export]
+macro_rules! transparent {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident $(<$($param:ident),+ $(,)?>)? (
+            $(#[$inner_attr:meta])* $inner_vis:vis $inner:ty
+            $(, $marker_vis:vis PhantomData<$marker:ty>)? $(,)?
+        );
+    )+) => {$(
+        $(#[$attr])*
+        #[repr(transparent)]
+        $vis struct $name $(<$($param),+>)? (
+            $(#[$inner_attr])* $inner_vis $inner
+            $(, $marker_vis ::core::marker::PhantomData<$marker>)?
+        );
+
+        // SAFETY: the struct is `#[repr(transparent)]` and the only other
+        // field is `PhantomData`
+        unsafe impl $(<$($param: 'static),+>)? $crate::Transparent for $name $(<$($param),+>)? {
+            type Inner = $inner;
+        }
+    )+};
+}
@@ -0,0 +1,316 @@
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "paranoid")]
+use core::alloc::Layout;
+use core::{fmt, marker::PhantomData};
+
+use crate::{checked_same_type, generic_transmute, value, Cismutable};
+
+/// Proof that `T` and `U` are the same type. It can only be obtained if they
+/// are, so having one allows infallible casts between them.
+///
+/// ```rust
+/// use cismute::TypeEq;
+///
+/// fn double<T: 'static>(val: T) -> Option<u32> {
+///     let eq = TypeEq::<T, u32>::new()?;
+///     Some(eq.cast(val) * 2)
+/// }
+///
+/// assert_eq!(double(21_u32), Some(42));
+/// assert_eq!(double(21_i32), None);
+/// ```
+///
+/// Both types can be unsized, e.g. `TypeEq<[T], [u8]>`; such proofs only cast
+/// references.
+///
+/// ```rust
+/// use cismute::TypeEq;
+///
+/// fn as_str<T: ?Sized + 'static>(val: &T) -> Option<&str> {
+///     Some(TypeEq::<T, str>::new()?.cast_ref(val))
+/// }
+///
+/// assert_eq!(as_str("hi"), Some("hi"));
+/// assert_eq!(as_str(&[1_u8][..]), None);
+/// ```
+pub struct TypeEq<T: ?Sized, U: ?Sized>(Invariant<T>, Invariant<U>);
+
+// Proofs must not be coerced to proofs about other types via variance
+type Invariant<T> = PhantomData<fn(PhantomData<T>) -> PhantomData<T>>;
+
+impl<X: ?Sized> TypeEq<X, X> {
+    /// Proof that a type is the same as itself. Works for types with lifetimes
+    /// too.
+    #[inline(always)]
+    #[must_use]
+    pub const fn refl() -> Self {
+        Self(PhantomData, PhantomData)
+    }
+}
+
+impl<T: ?Sized + 'static, U: ?Sized + 'static> TypeEq<T, U> {
+    nightly_const! {
+        /// Returns a proof if `T` and `U` are the same type. It's a `const fn`
+        /// with the `nightly` feature.
+        ///
+        /// ```rust
+        /// # #[cfg(feature = "nightly")] {
+        /// use cismute::TypeEq;
+        ///
+        /// const BYTES: Option<TypeEq<u8, u8>> = TypeEq::new();
+        /// const NOT_BYTES: Option<TypeEq<u32, u8>> = TypeEq::new();
+        /// assert!(BYTES.is_some() && NOT_BYTES.is_none());
+        /// # }
+        /// ```
+        ///
+        /// With the `paranoid` feature, aborts if `T` and `U` have the same
+        /// [`TypeId`](core::any::TypeId), but different names, and the casts
+        /// abort if the layouts of the values don't match.
+        #[inline(always)]
+        #[must_use]
+        pub fn new() -> Option<Self> {
+            if checked_same_type::<T, U>() {
+                Some(Self(PhantomData, PhantomData))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl<T, U> TypeEq<T, U> {
+    /// Casts `T` to `U`.
+    #[inline(always)]
+    pub fn cast(self, val: T) -> U {
+        // SAFETY: the proof exists, so `T` and `U` are the same type
+        unsafe { generic_transmute::<T, U>(val) }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> TypeEq<T, U> {
+    /// Casts `&T` to `&U`.
+    #[inline(always)]
+    pub fn cast_ref(self, val: &T) -> &U {
+        #[cfg(feature = "paranoid")]
+        let layout = Layout::for_value(val);
+        // SAFETY: the proof exists, so `T` and `U` are the same type
+        let val = unsafe { generic_transmute::<&T, &U>(val) };
+        #[cfg(feature = "paranoid")]
+        crate::check_pointee_layouts(Some(layout), Some(Layout::for_value(val)));
+        val
+    }
+
+    /// Casts `&mut T` to `&mut U`.
+    #[inline(always)]
+    pub fn cast_mut(self, val: &mut T) -> &mut U {
+        #[cfg(feature = "paranoid")]
+        let layout = Layout::for_value(&*val);
+        // SAFETY: the proof exists, so `T` and `U` are the same type
+        let val = unsafe { generic_transmute::<&mut T, &mut U>(val) };
+        #[cfg(feature = "paranoid")]
+        crate::check_pointee_layouts(Some(layout), Some(Layout::for_value(&*val)));
+        val
+    }
+
+    /// Proof that `U` is the same type as `T`, for casting back.
+    #[inline(always)]
+    #[must_use]
+    pub const fn flip(self) -> TypeEq<U, T> {
+        TypeEq(PhantomData, PhantomData)
+    }
+
+    /// Proof that `&'a T` and `&'a U` are the same type.
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_ref<'a>(self) -> TypeEq<&'a T, &'a U>
+    where
+        T: 'a,
+        U: 'a,
+    {
+        TypeEq(PhantomData, PhantomData)
+    }
+
+    /// Proof that `&'a mut T` and `&'a mut U` are the same type.
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_mut<'a>(self) -> TypeEq<&'a mut T, &'a mut U>
+    where
+        T: 'a,
+        U: 'a,
+    {
+        TypeEq(PhantomData, PhantomData)
+    }
+
+    /// Proof that `Box<T>` and `Box<U>` are the same type. This method
+    /// requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_box(self) -> TypeEq<Box<T>, Box<U>> {
+        TypeEq(PhantomData, PhantomData)
+    }
+}
+
+impl<T, U> TypeEq<T, U> {
+    /// Proof that `Option<T>` and `Option<U>` are the same type. Like the other
+    /// `in_*` methods, it lifts a proof into a container, without checking the
+    /// types again.
+    ///
+    /// ```rust
+    /// use cismute::TypeEq;
+    ///
+    /// fn first_byte<T: Copy + 'static>(items: &[T]) -> Option<u8> {
+    ///     let eq = TypeEq::<T, u8>::new()?;
+    ///     eq.in_option().cast(items.first().copied())
+    /// }
+    ///
+    /// assert_eq!(first_byte(&[42_u8]), Some(42));
+    /// assert_eq!(first_byte(&[42_u32]), None);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_option(self) -> TypeEq<Option<T>, Option<U>> {
+        TypeEq(PhantomData, PhantomData)
+    }
+
+    /// Proof that `Result<T, E>` and `Result<U, E>` are the same type.
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_result_ok<E>(self) -> TypeEq<Result<T, E>, Result<U, E>> {
+        TypeEq(PhantomData, PhantomData)
+    }
+
+    /// Proof that `Result<V, T>` and `Result<V, U>` are the same type.
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_result_err<V>(self) -> TypeEq<Result<V, T>, Result<V, U>> {
+        TypeEq(PhantomData, PhantomData)
+    }
+
+    /// Proof that `[T]` and `[U]` are the same type.
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_slice(self) -> TypeEq<[T], [U]> {
+        TypeEq(PhantomData, PhantomData)
+    }
+
+    /// Proof that `[T; N]` and `[U; N]` are the same type.
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_array<const N: usize>(self) -> TypeEq<[T; N], [U; N]> {
+        TypeEq(PhantomData, PhantomData)
+    }
+
+    /// Proof that `Vec<T>` and `Vec<U>` are the same type. This method
+    /// requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    #[must_use]
+    pub const fn in_vec(self) -> TypeEq<Vec<T>, Vec<U>> {
+        TypeEq(PhantomData, PhantomData)
+    }
+}
+
+impl<A, B, C, D> TypeEq<(A, B), (C, D)> {
+    /// Proof that `(A, B)` and `(C, D)` are the same type, built from proofs
+    /// about the elements without checking the types again. Arrays are covered
+    /// by [`TypeEq::in_array()`].
+    ///
+    /// ```rust
+    /// use cismute::TypeEq;
+    ///
+    /// fn as_entry<K: 'static, V: 'static>(pair: (K, V)) -> Option<(u8, char)> {
+    ///     let eq = TypeEq::tuple2(TypeEq::<K, u8>::new()?, TypeEq::<V, char>::new()?);
+    ///     Some(eq.cast(pair))
+    /// }
+    ///
+    /// assert_eq!(as_entry((1_u8, '!')), Some((1, '!')));
+    /// assert_eq!(as_entry((1_u8, "!")), None);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub const fn tuple2(first: TypeEq<A, C>, second: TypeEq<B, D>) -> Self {
+        let _ = (first, second);
+        Self(PhantomData, PhantomData)
+    }
+}
+
+impl<A, B, C, D, E, F> TypeEq<(A, B, C), (D, E, F)> {
+    /// Proof that `(A, B, C)` and `(D, E, F)` are the same type, built from
+    /// proofs about the elements. See [`TypeEq::tuple2()`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn tuple3(first: TypeEq<A, D>, second: TypeEq<B, E>, third: TypeEq<C, F>) -> Self {
+        let _ = (first, second, third);
+        Self(PhantomData, PhantomData)
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Clone for TypeEq<T, U> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Copy for TypeEq<T, U> {}
+
+impl<T: ?Sized, U: ?Sized> fmt::Debug for TypeEq<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TypeEq<{}, {}>",
+            core::any::type_name::<T>(),
+            core::any::type_name::<U>(),
+        )
+    }
+}
+
+/// Runs `f` with a proof that `T` and `U` are the same type, if they are.
+/// Keeps the specialized code in one block, and nothing can be cast outside of
+/// it.
+///
+/// ```rust
+/// fn sum<T: Copy + Into<u64> + 'static>(items: &[T]) -> u64 {
+///     cismute::with_proof::<T, u8, _>(|eq| {
+///         // e.g. a lookup table only makes sense for bytes
+///         items.iter().map(|&x| u64::from(eq.cast(x))).sum()
+///     })
+///     .unwrap_or_else(|| items.iter().map(|&x| x.into()).sum())
+/// }
+///
+/// assert_eq!(sum(&[1_u8, 2]), 3);
+/// assert_eq!(sum(&[1_u32, 2]), 3);
+/// ```
+#[inline(always)]
+pub fn with_proof<T: ?Sized + 'static, U: ?Sized + 'static, R>(
+    f: impl FnOnce(TypeEq<T, U>) -> R,
+) -> Option<R> {
+    TypeEq::new().map(f)
+}
+
+/// Same as [`cismute::value`](crate::value()), but also returns the proof that
+/// `T` and `U` are the same type, so other values can be cast without checking
+/// the types again.
+///
+/// ```rust
+/// fn add<T: Copy + 'static>(a: &T, b: T) -> Option<u32> {
+///     let (a, eq) = cismute::value_proved::<T, u32, _, _>(a).ok()?;
+///     Some(a + eq.cast(b))
+/// }
+///
+/// assert_eq!(add(&1_u32, 2), Some(3));
+/// assert_eq!(add(&1_u8, 2), None);
+/// ```
+#[inline(always)]
+#[cfg_attr(feature = "stats", track_caller)]
+pub fn value_proved<'a, T, U, RefT, RefU>(val: RefT) -> Result<(RefU, TypeEq<T, U>), RefT>
+where
+    T: ?Sized + 'static,
+    U: ?Sized + 'static,
+    RefT: Cismutable<'a, T, U, RefU>,
+{
+    // `value()` succeeding is the proof
+    value::<'a, T, U, RefT, RefU>(val).map(|val| (val, TypeEq(PhantomData, PhantomData)))
+}
@@ -0,0 +1,112 @@
+use core::fmt;
+
+use crate::SmallAny;
+
+/// A map from types to values of these types with a fixed capacity, e.g. for
+/// per-type singletons without an allocator. Values are stored in
+/// [`SmallAny`]s, so they have to fit into `S` unless the `alloc` feature is
+/// enabled.
+///
+/// Lookups are linear, which is fast for the small capacities it's made for.
+///
+/// ```rust
+/// use cismute::TypeMap;
+///
+/// #[derive(Debug)]
+/// struct Config {
+///     verbose: bool,
+/// }
+///
+/// let mut map = TypeMap::<4>::new();
+/// map.insert(Config { verbose: true }).unwrap();
+/// map.insert(42_u32).unwrap();
+///
+/// assert!(map.get::<Config>().unwrap().verbose);
+/// *map.get_mut::<u32>().unwrap() += 1;
+/// assert_eq!(map.remove::<u32>(), Some(43));
+/// assert_eq!(map.get::<u32>(), None);
+/// ```
+pub struct TypeMap<const N: usize, S = [usize; 2]> {
+    slots: [Option<SmallAny<S>>; N],
+}
+
+impl<const N: usize, S> TypeMap<N, S> {
+    /// Creates an empty map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            slots: core::array::from_fn(|_| None),
+        }
+    }
+
+    fn position<T: 'static>(&self) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.as_ref().is_some_and(SmallAny::is::<T>))
+    }
+
+    /// Inserts a value, returning the previous value of the same type, if any.
+    /// Returns the value back if the map is full.
+    pub fn insert<T: 'static>(&mut self, val: T) -> Result<Option<T>, T> {
+        if let Some(slot) = self.get_mut::<T>() {
+            return Ok(Some(core::mem::replace(slot, val)));
+        }
+        match self.slots.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(SmallAny::new(val));
+                Ok(None)
+            }
+            None => Err(val),
+        }
+    }
+
+    /// Returns a reference to the value of type `T`, if there's one.
+    #[must_use]
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.slots[self.position::<T>()?].as_ref()?.downcast_ref()
+    }
+
+    /// Returns a mutable reference to the value of type `T`, if there's one.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        let position = self.position::<T>()?;
+        self.slots[position].as_mut()?.downcast_mut()
+    }
+
+    /// Removes the value of type `T` and returns it, if there's one.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        let position = self.position::<T>()?;
+        self.slots[position].take()?.downcast().ok()
+    }
+
+    /// Returns `true` if there's a value of type `T`.
+    #[must_use]
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.position::<T>().is_some()
+    }
+
+    /// Returns the number of values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Returns `true` if the map has no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+}
+
+impl<const N: usize, S> Default for TypeMap<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, S> fmt::Debug for TypeMap<N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeMap")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
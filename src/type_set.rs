@@ -0,0 +1,96 @@
+use core::marker::PhantomData;
+
+use crate::same_type;
+
+/// A set of types, so dispatchers working with the same types can share one
+/// definition. It's built with the [`TypeSet!`](crate::TypeSet!) macro, and
+/// the types can be checked and visited one by one:
+///
+/// ```rust
+/// use cismute::{TypeSet, VisitType};
+///
+/// type Numbers = TypeSet![i32, u32, f32];
+///
+/// assert!(Numbers::contains::<u32>());
+/// assert!(!Numbers::contains::<u8>());
+/// assert_eq!(Numbers::LEN, 3);
+///
+/// struct Names(Vec<&'static str>);
+///
+/// impl VisitType for Names {
+///     fn visit<T: ?Sized + 'static>(&mut self) {
+///         self.0.push(std::any::type_name::<T>());
+///     }
+/// }
+///
+/// let mut names = Names(Vec::new());
+/// Numbers::for_each(&mut names);
+/// assert_eq!(names.0, ["i32", "u32", "f32"]);
+/// ```
+///
+/// [`Switch::finish_checked()`](crate::Switch::finish_checked()) panics when
+/// a value of a type in the set reaches a switch with no arm for it.
+pub trait TypeSet: 'static {
+    /// Number of types in the set, duplicates included.
+    const LEN: usize;
+
+    /// Returns `true` if `T` is one of the types of the set.
+    fn contains<T: ?Sized + 'static>() -> bool;
+
+    /// Calls [`visitor.visit()`](VisitType::visit()) for every type of the
+    /// set, in order.
+    fn for_each<V: VisitType>(visitor: &mut V);
+}
+
+/// A generic callback for [`TypeSet::for_each()`].
+pub trait VisitType {
+    /// Called with a type of the set.
+    fn visit<T: ?Sized + 'static>(&mut self);
+}
+
+/// The empty [`TypeSet`](trait@TypeSet), the end of a list built by
+/// [`TypeSet!`](crate::TypeSet!).
+pub struct Nil(());
+
+/// A [`TypeSet`](trait@TypeSet) of `H` and the types of `T`, built by
+/// [`TypeSet!`](crate::TypeSet!).
+pub struct Cons<H: ?Sized, T>(PhantomData<T>, PhantomData<H>);
+
+impl TypeSet for Nil {
+    const LEN: usize = 0;
+
+    #[inline(always)]
+    fn contains<T: ?Sized + 'static>() -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn for_each<V: VisitType>(_visitor: &mut V) {}
+}
+
+impl<H: ?Sized + 'static, T: TypeSet> TypeSet for Cons<H, T> {
+    const LEN: usize = T::LEN + 1;
+
+    #[inline(always)]
+    fn contains<U: ?Sized + 'static>() -> bool {
+        same_type::<U, H>() || T::contains::<U>()
+    }
+
+    #[inline(always)]
+    fn for_each<V: VisitType>(visitor: &mut V) {
+        visitor.visit::<H>();
+        T::for_each(visitor);
+    }
+}
+
+/// Builds a [`TypeSet`](trait@crate::TypeSet) type of the listed types, e.g.
+/// `type Numbers = cismute::TypeSet![i32, u32, f32];`.
+#[macro_export]
+macro_rules! TypeSet {
+    () => {
+        $crate::Nil
+    };
+    ($head:ty $(, $tail:ty)* $(,)?) => {
+        $crate::Cons<$head, $crate::TypeSet![$($tail),*]>
+    };
+}
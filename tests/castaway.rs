@@ -0,0 +1,36 @@
+//! Common `castaway` migration patterns, which must compile unchanged with
+//! `cismute::castaway::cast!` apart from the import.
+#![cfg(all(feature = "castaway", feature = "alloc"))]
+
+use cismute::castaway::cast;
+
+fn to_string<T>(val: T) -> Result<String, T> {
+    cast!(val, String)
+}
+
+fn as_bytes<T>(val: &T) -> Option<&Vec<u8>> {
+    cast!(val, &Vec<u8>).ok()
+}
+
+fn as_boxed_str<T>(val: &mut T) -> Option<&mut Box<str>> {
+    cast!(val, &mut Box<str>).ok()
+}
+
+#[test]
+fn owned_string() {
+    assert_eq!(to_string(String::from("hi")).unwrap(), "hi");
+    assert_eq!(to_string("hi").unwrap_err(), "hi");
+    assert_eq!(to_string(42_u32).unwrap_err(), 42);
+}
+
+#[test]
+fn references() {
+    assert_eq!(as_bytes(&vec![1_u8, 2]), Some(&vec![1, 2]));
+    assert_eq!(as_bytes(&vec![1_u16, 2]), None);
+    assert_eq!(as_bytes(&(vec![1_u8], "borrowed")), None);
+
+    let mut boxed: Box<str> = Box::from("hi");
+    as_boxed_str(&mut boxed).unwrap().make_ascii_uppercase();
+    assert_eq!(&*boxed, "HI");
+    assert_eq!(as_boxed_str(&mut String::from("hi")), None);
+}
@@ -0,0 +1,127 @@
+//! Checks that matching with `cismute` is zero-cost: every `*_cismute`
+//! function in `examples/codegen_fixtures.rs` must compile to the same LLVM IR
+//! as the matching `*_expected` function in a release build.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    path::Path,
+    process::Command,
+};
+
+// Renames values and labels in order of appearance, so the names given by
+// the compiler don't matter
+fn normalize(body: &[&str]) -> Vec<String> {
+    let mut names = HashMap::new();
+    let mut rename = |name: &str| {
+        let next = names.len();
+        format!("%{}", names.entry(name.to_owned()).or_insert(next))
+    };
+    let is_name = |c: char| c.is_ascii_alphanumeric() || "_.$-".contains(c);
+    body.iter()
+        .map(|line| {
+            // metadata like `!dbg !5` and attribute groups differ between functions
+            let line = line.split(", !").next().unwrap_or_default();
+            let line = line.split(" #").next().unwrap_or_default();
+            if let Some(label) = line
+                .strip_suffix(':')
+                .filter(|label| label.chars().all(is_name))
+            {
+                return format!("{}:", rename(label));
+            }
+            let mut normalized = String::new();
+            let mut rest = line;
+            while let Some(start) = rest.find('%') {
+                normalized.push_str(&rest[..start]);
+                let name = &rest[start + 1..];
+                let end = name.find(|c| !is_name(c)).unwrap_or(name.len());
+                normalized.push_str(&rename(&name[..end]));
+                rest = &name[end..];
+            }
+            normalized.push_str(rest);
+            normalized
+        })
+        .collect()
+}
+
+fn function_bodies(ir: &str) -> BTreeMap<&str, Vec<String>> {
+    let mut bodies = BTreeMap::new();
+    let mut aliases = Vec::new();
+    let mut lines = ir.lines();
+    while let Some(line) = lines.next() {
+        // identical functions are merged into aliases
+        if let Some((name, target)) = line
+            .strip_prefix('@')
+            .and_then(|line| line.split_once(" = "))
+            .filter(|(_, definition)| definition.contains(" alias "))
+            .and_then(|(name, definition)| Some((name, definition.rsplit_once(" @")?.1)))
+        {
+            aliases.push((name, target));
+            continue;
+        }
+        let Some(name) = line
+            .strip_prefix("define ")
+            .and_then(|line| line.split('@').nth(1))
+            .and_then(|name| name.split('(').next())
+        else {
+            continue;
+        };
+        let body: Vec<_> = lines.by_ref().take_while(|&line| line != "}").collect();
+        bodies.insert(name, normalize(&body));
+    }
+    for (name, target) in aliases {
+        if let Some(body) = bodies.get(target).cloned() {
+            bodies.insert(name, body);
+        }
+    }
+    bodies
+}
+
+#[test]
+fn zero_cost() {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned()))
+        .args([
+            "rustc",
+            "--quiet",
+            "--release",
+            "--example",
+            "codegen_fixtures",
+        ])
+        .args(["--features", "switch", "--target-dir"])
+        .arg(&target_dir)
+        .args(["--", "--emit=llvm-ir", "-Cdebuginfo=0"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "failed to build the fixtures");
+
+    let examples = target_dir.join("release").join("examples");
+    let ir_path = fs::read_dir(&examples)
+        .expect("failed to read the build directory")
+        .map(|entry| entry.expect("failed to read the build directory").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ll"))
+        .max_by_key(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .expect("no LLVM IR emitted");
+    let ir = fs::read_to_string(ir_path).expect("failed to read LLVM IR");
+    let bodies = function_bodies(&ir);
+
+    let mut checked = 0;
+    for (name, body) in &bodies {
+        let Some(fixture) = name.strip_suffix("_cismute") else {
+            continue;
+        };
+        let expected = &bodies[format!("{fixture}_expected").as_str()];
+        assert_eq!(body, expected, "`{name}` isn't zero-cost");
+        checked += 1;
+    }
+    assert!(checked > 0, "no fixtures found");
+
+    // a copy of the page would be a call to `memcpy`
+    assert!(
+        !bodies["large_cismute"]
+            .iter()
+            .any(|line| line.contains("@llvm.memcpy")),
+        "`large_cismute` copies the value",
+    );
+}
@@ -0,0 +1,356 @@
+//! Exercises every way of cismuting with drop-tracking values, so double
+//! drops, leaks and invalid pointers are caught by Miri:
+//! `cargo miri test --test miri --features switch,alloc`.
+
+use std::{cell::Cell, marker::PhantomData, rc::Rc};
+
+use cismute::{Handler, JumpTable, Switch};
+
+// Counts how many times it was dropped
+#[derive(Debug)]
+struct Tracked {
+    value: u32,
+    drops: Rc<Cell<usize>>,
+}
+
+impl Tracked {
+    fn new(value: u32) -> (Self, Rc<Cell<usize>>) {
+        let drops = Rc::new(Cell::new(0));
+        (
+            Self {
+                value,
+                drops: Rc::clone(&drops),
+            },
+            drops,
+        )
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+#[test]
+fn owned_moves_the_value() {
+    let (val, drops) = Tracked::new(1);
+    let val = cismute::owned::<Tracked, Tracked>(val).unwrap();
+    assert_eq!(drops.get(), 0);
+    assert_eq!(val.value, 1);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn owned_mismatch_returns_the_value() {
+    let (val, drops) = Tracked::new(1);
+    let val = cismute::owned::<Tracked, u32>(val).unwrap_err();
+    assert_eq!(drops.get(), 0);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn owned_zst_and_large_values() {
+    cismute::owned::<(), ()>(()).unwrap();
+    cismute::owned::<PhantomData<Tracked>, PhantomData<Tracked>>(PhantomData).unwrap();
+    let big = cismute::owned::<[u64; 512], [u64; 512]>([7; 512]).unwrap();
+    assert!(big.iter().all(|&x| x == 7));
+    let niche = cismute::owned::<Option<Box<u8>>, Option<Box<u8>>>(Some(Box::new(3))).unwrap();
+    assert_eq!(niche.as_deref(), Some(&3));
+}
+
+#[test]
+fn references() {
+    let (mut val, drops) = Tracked::new(1);
+    assert_eq!(
+        cismute::reference::<Tracked, Tracked>(&val).unwrap().value,
+        1
+    );
+    assert!(cismute::reference::<Tracked, u32>(&val).is_err());
+    cismute::mutable::<Tracked, Tracked>(&mut val)
+        .unwrap()
+        .value = 2;
+    assert!(cismute::mutable::<Tracked, u32>(&mut val).is_err());
+    assert_eq!(val.value, 2);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn unsized_references() {
+    let mut bytes = *b"abc";
+    assert_eq!(cismute::reference::<str, str>("abc").unwrap(), "abc");
+    cismute::mutable::<[u8], [u8]>(&mut bytes[..]).unwrap()[0] = b'x';
+    assert_eq!(&bytes, b"xbc");
+    assert!(cismute::reference::<[u8], str>(&bytes[..]).is_err());
+}
+
+#[test]
+fn value_with() {
+    let (val, drops) = Tracked::new(1);
+    let val = cismute::value_with(cismute::Pair::<Tracked, Tracked>::of(), val).unwrap();
+    let val = cismute::value_with(cismute::Pair::<Tracked, u8>::of(), val).unwrap_err();
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn builder() {
+    let (val, drops) = Tracked::new(1);
+    let value = Switch::owned(val)
+        .on::<u32>(|_| 0)
+        .on::<Tracked>(|x| x.value)
+        .on::<Tracked>(|_| unreachable!())
+        .fallback(|_| unreachable!());
+    assert_eq!(value, 1);
+    assert_eq!(drops.get(), 1);
+
+    let (val, drops) = Tracked::new(1);
+    let val = Switch::owned(val).on::<u32>(|_| ()).finish().unwrap_err();
+    assert_eq!(drops.get(), 0);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+
+    let (mut val, drops) = Tracked::new(1);
+    Switch::mutable(&mut val)
+        .on::<Tracked>(|x| x.value += 1)
+        .fallback(|_| unreachable!());
+    assert_eq!(val.value, 2);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn switch_macro() {
+    fn run<T: 'static>(val: T) -> Result<u32, T> {
+        cismute::switch!(val; T => {
+            _: u32 => 0,
+            x: Tracked => x.value,
+        })
+    }
+
+    let (val, drops) = Tracked::new(1);
+    assert_eq!(run(val).unwrap(), 1);
+    assert_eq!(drops.get(), 1);
+    run(String::from("unmatched")).unwrap_err();
+}
+
+#[cfg(feature = "switch")]
+#[test]
+fn switch_function() {
+    fn run<T: 'static>(val: T) -> Result<u32, T> {
+        cismute::switch(val, (|_: u32| 0, |x: Tracked| x.value))
+    }
+
+    let (val, drops) = Tracked::new(1);
+    assert_eq!(run(val).unwrap(), 1);
+    assert_eq!(drops.get(), 1);
+
+    let (val, drops) = Tracked::new(1);
+    let wrapped = run(Some(val)).unwrap_err();
+    assert_eq!(drops.get(), 0);
+    drop(wrapped);
+    assert_eq!(drops.get(), 1);
+
+    let (mut val, drops) = Tracked::new(1);
+    cismute::switch_mutable(&mut val, (|x: &mut Tracked| x.value += 1,)).unwrap();
+    assert_eq!(val.value, 2);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn jump_table() {
+    let table = JumpTable::new([
+        Handler::owned(|x: Tracked| x.value),
+        Handler::reference(|x: &Tracked| x.value + 10),
+        Handler::mutable(|x: &mut Tracked| {
+            x.value += 1;
+            x.value
+        }),
+    ]);
+
+    let (mut val, drops) = Tracked::new(1);
+    assert_eq!(table.reference(&val).unwrap(), 11);
+    assert_eq!(table.mutable(&mut val).unwrap(), 2);
+    assert_eq!(table.owned(val).unwrap(), 2);
+    assert_eq!(drops.get(), 1);
+
+    let (val, drops) = Tracked::new(1);
+    let val = table.owned(Some(val)).unwrap_err();
+    assert_eq!(drops.get(), 0);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+cismute::transparent! {
+    #[derive(Debug)]
+    struct Wrapper(Tracked);
+}
+
+#[test]
+fn transparent() {
+    let (val, drops) = Tracked::new(1);
+    let mut wrapper = cismute::wrap::<Tracked, Wrapper, _>(val).unwrap();
+    cismute::unwrap::<Wrapper, Tracked, _>(&mut wrapper)
+        .unwrap()
+        .value = 2;
+    let val = cismute::unwrap::<Wrapper, Tracked, _>(wrapper).unwrap();
+    assert_eq!(val.value, 2);
+    assert_eq!(drops.get(), 0);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[derive(Debug)]
+struct Borrowed<'a>(&'a Tracked);
+
+// SAFETY: `Borrowed` only has one lifetime and is covariant in it
+unsafe impl cismute::Transient for Borrowed<'static> {
+    type Borrowed<'a> = Borrowed<'a>;
+}
+
+#[test]
+fn lifetimes() {
+    let (val, drops) = Tracked::new(1);
+    let borrowed = Borrowed(&val);
+    assert!(cismute::reference_lifetime_free::<_, u32>(&borrowed).is_err());
+    let value = cismute::reference_transient::<_, Borrowed<'static>>(&borrowed)
+        .unwrap()
+        .0
+        .value;
+    assert_eq!(value, 1);
+    let borrowed: Borrowed<'_> = cismute::owned_transient::<_, Borrowed<'static>>(borrowed)
+        .ok()
+        .unwrap();
+    assert_eq!(borrowed.0.value, 1);
+    let mut x = 1_u32;
+    *cismute::mutable_lifetime_free::<u32, u32>(&mut x).unwrap() += 1;
+    assert_eq!(cismute::owned_lifetime_free::<u32, u32>(x), Ok(2));
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn small_any() {
+    let (val, drops) = Tracked::new(1);
+    let any = cismute::SmallAny::<[usize; 4]>::new(val);
+    assert!(any.downcast_ref::<u32>().is_none());
+    let any = any.downcast::<u32>().unwrap_err();
+    assert_eq!(drops.get(), 0);
+    let val = any.downcast::<Tracked>().unwrap();
+    assert_eq!(drops.get(), 0);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+
+    let (val, drops) = Tracked::new(1);
+    let mut any = cismute::SmallAny::<[usize; 4]>::new(val);
+    any.downcast_mut::<Tracked>().unwrap().value = 2;
+    assert_eq!(any.as_any_ref().downcast_ref::<Tracked>().unwrap().value, 2);
+    drop(any);
+    assert_eq!(drops.get(), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn small_any_boxed() {
+    let (val, drops) = Tracked::new(1);
+    let any = cismute::SmallAny::<usize>::new((val, [0_u64; 8]));
+    let (val, _) = any.downcast::<(Tracked, [u64; 8])>().unwrap();
+    assert_eq!(drops.get(), 0);
+    drop(val);
+    assert_eq!(drops.get(), 1);
+
+    let (val, drops) = Tracked::new(1);
+    let mut any = cismute::SmallAny::<usize>::new(val);
+    any.as_any_mut().downcast_mut::<Tracked>().unwrap().value = 2;
+    assert_eq!(any.downcast_ref::<Tracked>().unwrap().value, 2);
+    drop(any);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn erased_slices() {
+    let (a, drops) = Tracked::new(1);
+    let (b, _) = Tracked::new(2);
+    let mut items = [a, b];
+
+    let mut slice = cismute::AnySliceMut::new(&mut items);
+    assert!(slice.as_mut_slice::<u32>().is_none());
+    slice
+        .get_mut(1)
+        .unwrap()
+        .downcast_mut::<Tracked>()
+        .unwrap()
+        .value = 3;
+    assert!(slice.get_mut(2).is_none());
+    let slice = slice.as_any_slice();
+    assert_eq!(
+        slice
+            .get(1)
+            .unwrap()
+            .downcast_ref::<Tracked>()
+            .unwrap()
+            .value,
+        3
+    );
+    assert_eq!(slice.as_slice::<Tracked>().unwrap()[0].value, 1);
+
+    drop(items);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn erased_across_threads() {
+    use std::{any::Any, thread};
+
+    let mut items = [String::from("a"), String::from("b")];
+    let mut slice = cismute::AnySliceMut::<dyn Any + Send>::bounded(&mut items);
+    thread::scope(|s| {
+        s.spawn(|| slice.as_mut_slice::<String>().unwrap()[1].push('!'));
+    });
+    let slice = cismute::AnySlice::<dyn Any + Sync>::bounded(&items);
+    thread::scope(|s| {
+        s.spawn(|| assert_eq!(slice.as_slice::<String>().unwrap()[1], "b!"));
+    });
+
+    let any = cismute::SmallAny::<[usize; 6], dyn Any + Send>::bounded(items);
+    let items = thread::spawn(move || any.downcast::<[String; 2]>().unwrap())
+        .join()
+        .unwrap();
+    assert_eq!(items, ["a", "b!"]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn erased_vec() {
+    let mut column = cismute::ErasedVec::new::<Tracked>();
+    let mut counters = Vec::new();
+    for value in 0..10 {
+        let (val, drops) = Tracked::new(value);
+        column.push(val).unwrap();
+        counters.push(drops);
+    }
+    assert_eq!(column.push(1_u32), Err(1));
+    assert!(column.capacity() >= 10);
+
+    let last = column.pop::<Tracked>().unwrap();
+    assert_eq!(last.value, 9);
+    drop(last);
+    column.as_mut_slice::<Tracked>().unwrap()[0].value = 42;
+    assert_eq!(column.as_slice::<Tracked>().unwrap()[0].value, 42);
+
+    column.truncate(5);
+    assert_eq!(column.len(), 5);
+    assert!(counters[5..].iter().all(|drops| drops.get() == 1));
+    assert!(counters[..5].iter().all(|drops| drops.get() == 0));
+    drop(column);
+    assert!(counters.iter().all(|drops| drops.get() == 1));
+
+    let mut units = cismute::ErasedVec::new::<()>();
+    units.push(()).unwrap();
+    assert_eq!(units.as_slice::<()>(), Some(&[()][..]));
+    assert_eq!(units.pop::<()>(), Some(()));
+}
@@ -0,0 +1,200 @@
+//! Property-style checks with pseudo-random values: matched cismutes must keep
+//! the value intact and mismatched ones must return it untouched. Values are
+//! generated with a small deterministic PRNG instead of `proptest`, so the
+//! crate stays free of dependencies.
+
+use std::{cell::Cell, fmt::Debug, num::NonZeroU32, rc::Rc};
+
+const CASES: usize = 256;
+
+// xorshift64*, deterministic so failures are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+trait Arbitrary: Clone + Debug + PartialEq + 'static {
+    fn arbitrary(rng: &mut Rng) -> Self;
+}
+
+macro_rules! impl_arbitrary_int {
+    ($($type:ty),+) => {$(
+        impl Arbitrary for $type {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            fn arbitrary(rng: &mut Rng) -> Self {
+                rng.next() as $type
+            }
+        }
+    )+};
+}
+
+impl_arbitrary_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl Arbitrary for u128 {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        (u128::from(rng.next()) << 64) | u128::from(rng.next())
+    }
+}
+
+impl Arbitrary for () {
+    fn arbitrary(_: &mut Rng) -> Self {}
+}
+
+impl Arbitrary for bool {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        rng.next() & 1 == 0
+    }
+}
+
+impl Arbitrary for char {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        char::from_u32(u32::arbitrary(rng) % 0x11_0000).unwrap_or('?')
+    }
+}
+
+impl Arbitrary for NonZeroU32 {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        NonZeroU32::new(u32::arbitrary(rng)).unwrap_or(NonZeroU32::MIN)
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        bool::arbitrary(rng).then(|| T::arbitrary(rng))
+    }
+}
+
+impl<T: Arbitrary, const N: usize> Arbitrary for [T; N] {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        std::array::from_fn(|_| T::arbitrary(rng))
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Box<T> {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        Box::new(T::arbitrary(rng))
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        let len = rng.next() % 16;
+        (0..len).map(|_| T::arbitrary(rng)).collect()
+    }
+}
+
+impl Arbitrary for String {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        Vec::<char>::arbitrary(rng).into_iter().collect()
+    }
+}
+
+// Counts drops of all its clones
+#[derive(Clone, Debug)]
+struct Tracked {
+    value: u64,
+    drops: Rc<Cell<usize>>,
+}
+
+impl PartialEq for Tracked {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+impl Arbitrary for Tracked {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        Self {
+            value: rng.next(),
+            drops: Rc::default(),
+        }
+    }
+}
+
+fn drops<T: 'static>(val: &T) -> Option<usize> {
+    cismute::reference::<T, Tracked>(val)
+        .ok()
+        .map(|tracked| tracked.drops.get())
+}
+
+// Checks `T` against itself and against `Other`, which must be a different
+// type
+fn check<T: Arbitrary, Other: Debug + 'static>(seed: u64) {
+    let mut rng = Rng(seed);
+    for _ in 0..CASES {
+        let original = T::arbitrary(&mut rng);
+        let before = drops(&original);
+
+        let matched = cismute::owned::<T, T>(original.clone()).expect("same type didn't match");
+        assert_eq!(matched, original);
+        let mismatched = cismute::owned::<T, Other>(matched).expect_err("different types matched");
+        assert_eq!(mismatched, original);
+
+        let mut copy = mismatched;
+        assert_eq!(cismute::reference::<T, T>(&copy).ok(), Some(&original));
+        assert!(cismute::reference::<T, Other>(&copy).is_err());
+        assert!(cismute::mutable::<T, T>(&mut copy).is_ok());
+        assert!(cismute::mutable::<T, Other>(&mut copy).is_err());
+        assert_eq!(copy, original);
+
+        // only clones were moved around, so the original wasn't dropped
+        assert_eq!(drops(&original), before);
+        drop(copy);
+        assert_eq!(drops(&original), before.map(|count| count + 1));
+    }
+}
+
+#[test]
+fn integers() {
+    check::<u8, i8>(1);
+    check::<u16, i16>(2);
+    check::<u32, i32>(3);
+    check::<u64, i64>(4);
+    check::<u128, u64>(5);
+    check::<usize, u64>(6);
+    check::<isize, i64>(7);
+}
+
+#[test]
+fn small_types() {
+    check::<(), [u8; 0]>(8);
+    check::<[u8; 0], ()>(9);
+    check::<bool, u8>(10);
+    check::<char, u32>(11);
+}
+
+#[test]
+fn niches() {
+    check::<Option<NonZeroU32>, u32>(12);
+    check::<Option<bool>, Option<u8>>(13);
+    check::<Option<Box<u64>>, Box<u64>>(14);
+    check::<Option<char>, char>(15);
+}
+
+#[test]
+fn large_values() {
+    check::<[u64; 64], [i64; 64]>(16);
+    check::<[u8; 4096], [u8; 4095]>(17);
+}
+
+#[test]
+fn heap_and_drops() {
+    check::<String, Vec<char>>(18);
+    check::<Vec<u16>, Vec<i16>>(19);
+    check::<Tracked, u64>(20);
+    check::<Box<Tracked>, Tracked>(21);
+    check::<Option<Tracked>, Tracked>(22);
+    check::<Vec<Tracked>, Tracked>(23);
+}